@@ -0,0 +1,15 @@
+// Runs every Quantum Kata solution in order, printing the states they dump.
+// The katas themselves live in tests/katas.rs, where cargo test checks them.
+#[path = "../tests/katas.rs"]
+mod katas;
+
+use qsharp_rs::QuantumError;
+
+fn main() -> Result<(), QuantumError> {
+    for (name, kata) in katas::KATAS {
+        println!("=== {} ===", name);
+        kata()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,957 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet
+};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    matrix,
+    complex,
+    error,
+    system,
+    float
+};
+
+use matrix::{
+    Matrix,
+    Gate
+};
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use system::System;
+use float::Float;
+
+// A gate angle: a number, or a named symbol that Circuit::bind fills in
+// before the circuit runs, so one circuit can be reused across values
+#[derive(Clone, Debug, PartialEq)]
+pub enum Parameter<T: Float = f64> {
+    Value(T),
+    Symbol(String)
+}
+impl<T: Float> From<T> for Parameter<T> {
+    fn from ( value: T ) -> Self {
+        Parameter::Value(value)
+    }
+}
+impl<T: Float> From<&str> for Parameter<T> {
+    fn from ( name: &str ) -> Self {
+        Parameter::Symbol(name.to_string())
+    }
+}
+impl<T: Float> fmt::Display for Parameter<T> {
+    fn fmt ( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        match self {
+            Parameter::Value(value) => write!(f, "{}", value.to_f64()),
+            Parameter::Symbol(name) => write!(f, "{}", name)
+        }
+    }
+}
+impl<T: Float> Parameter<T> {
+    pub fn value ( &self ) -> Result<T> {
+        match self {
+            Parameter::Value(value) => Ok(*value),
+            Parameter::Symbol(name) => Err(QuantumError::UnboundParameter(name.clone()))
+        }
+    }
+}
+
+// The gates a System applies by name. Keeping the name rather than just the
+// matrix is what lets noise models, the serialized form and the optimizer
+// recognize them.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation<T: Float = f64> {
+    I,
+    X,
+    Y,
+    Z,
+    H,
+    S,
+    T,
+    S_adj,
+    T_adj,
+    R_x(Parameter<T>),
+    R_y(Parameter<T>),
+    R_z(Parameter<T>),
+    R_1(Parameter<T>),
+    SWAP
+}
+impl<T: Float> fmt::Display for Operation<T> {
+    fn fmt ( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        match self.parameter() {
+            Some(parameter) => write!(f, "{}({})", self.name(), parameter),
+            None => write!(f, "{}", self.name())
+        }
+    }
+}
+impl<T: Float> Operation<T> {
+    pub fn name ( &self ) -> &'static str {
+        match self {
+            Operation::I => "I",
+            Operation::X => "X",
+            Operation::Y => "Y",
+            Operation::Z => "Z",
+            Operation::H => "H",
+            Operation::S => "S",
+            Operation::T => "T",
+            Operation::S_adj => "S_adj",
+            Operation::T_adj => "T_adj",
+            Operation::R_x(_) => "R_x",
+            Operation::R_y(_) => "R_y",
+            Operation::R_z(_) => "R_z",
+            Operation::R_1(_) => "R_1",
+            Operation::SWAP => "SWAP"
+        }
+    }
+    pub fn parameter ( &self ) -> Option<&Parameter<T>> {
+        match self {
+            Operation::R_x(theta) | Operation::R_y(theta) | Operation::R_z(theta) | Operation::R_1(theta) => Some(theta),
+            _ => None
+        }
+    }
+    // Registers the gate acts on, not counting controls
+    pub fn width ( &self ) -> usize {
+        match self {
+            Operation::SWAP => 2,
+            _ => 1
+        }
+    }
+    pub fn matrix ( &self ) -> Result<Matrix<T>> {
+        Ok(match self {
+            Operation::I => Gate::I(),
+            Operation::X => Gate::X(),
+            Operation::Y => Gate::Y(),
+            Operation::Z => Gate::Z(),
+            Operation::H => Gate::H(),
+            Operation::S => Gate::S(),
+            Operation::T => Gate::T(),
+            Operation::S_adj => Gate::S_adj(),
+            Operation::T_adj => Gate::T_adj(),
+            Operation::R_x(theta) => Gate::R_x( theta.value()? ),
+            Operation::R_y(theta) => Gate::R_y( theta.value()? ),
+            Operation::R_z(theta) => Gate::R_z( theta.value()? ),
+            Operation::R_1(theta) => Gate::R_1( theta.value()? ),
+            Operation::SWAP => {
+                let mut ret = Matrix::identity( 4 );
+                ret.swap_rows( 1, 2 );
+                ret
+            }
+        })
+    }
+    fn from_name ( name: &str, parameter: Option<Parameter<T>> ) -> Option<Self> {
+        Some(match (name, parameter) {
+            ("I", None) => Operation::I,
+            ("X", None) => Operation::X,
+            ("Y", None) => Operation::Y,
+            ("Z", None) => Operation::Z,
+            ("H", None) => Operation::H,
+            ("S", None) => Operation::S,
+            ("T", None) => Operation::T,
+            ("S_adj", None) => Operation::S_adj,
+            ("T_adj", None) => Operation::T_adj,
+            ("R_x", Some(theta)) => Operation::R_x(theta),
+            ("R_y", Some(theta)) => Operation::R_y(theta),
+            ("R_z", Some(theta)) => Operation::R_z(theta),
+            ("R_1", Some(theta)) => Operation::R_1(theta),
+            ("SWAP", None) => Operation::SWAP,
+            _ => return None
+        })
+    }
+    // The same gate with its parameter replaced
+    fn with_parameter ( &self, parameter: Parameter<T> ) -> Self {
+        match self {
+            Operation::R_x(_) => Operation::R_x(parameter),
+            Operation::R_y(_) => Operation::R_y(parameter),
+            Operation::R_z(_) => Operation::R_z(parameter),
+            Operation::R_1(_) => Operation::R_1(parameter),
+            _ => self.clone()
+        }
+    }
+    // S and T swap with their named adjoints, so noise models still see a
+    // phase gate by name; a rotation is undone by its negated angle, which
+    // needs the angle to be bound
+    fn inverse ( &self ) -> Result<Self> {
+        Ok(match self {
+            Operation::S => Operation::S_adj,
+            Operation::T => Operation::T_adj,
+            Operation::S_adj => Operation::S,
+            Operation::T_adj => Operation::T,
+            operation => match operation.parameter() {
+                Some(theta) => operation.with_parameter( Parameter::Value(-theta.value()?) ),
+                None => operation.clone()
+            }
+        })
+    }
+}
+
+// One step of a circuit
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction<T: Float = f64> {
+    // A named gate on `targets` (two for SWAP, otherwise one), applied only
+    // where every control is |1>
+    Gate {
+        operation: Operation<T>,
+        controls: Vec<usize>,
+        targets: Vec<usize>
+    },
+    // A 2^k x 2^k unitary on k registers, targets[0] being its most
+    // significant qubit, as with System::apply
+    Unitary {
+        matrix: Matrix<T>,
+        targets: Vec<usize>
+    },
+    // Measures `qubit` in the computational basis into classical bit `bit`
+    Measure {
+        qubit: usize,
+        bit: usize
+    },
+    // Runs `instruction` only if classical bit `bit` holds `value`
+    Conditional {
+        bit: usize,
+        value: bool,
+        instruction: Box<Instruction<T>>
+    }
+}
+impl<T: Float> fmt::Display for Instruction<T> {
+    fn fmt ( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        match self {
+            Instruction::Gate { operation, controls, targets } => {
+                if !controls.is_empty() {
+                    let controls: Vec<String> = controls.iter().map(|control| format!("q{}", control)).collect();
+                    write!(f, "ctrl({}) ", controls.join(", "))?;
+                }
+                write!(f, "{}", operation)?;
+                for target in targets {
+                    write!(f, " q{}", target)?;
+                }
+                Ok(())
+            },
+            Instruction::Unitary { matrix, targets } => {
+                let rows: Vec<String> = (0..matrix.rows)
+                    .map(|r| {
+                        let entries: Vec<String> = matrix[r].iter().map(|entry| format!("{} {}", entry.a.to_f64(), entry.b.to_f64())).collect();
+                        entries.join(", ")
+                    })
+                    .collect();
+                write!(f, "unitary({})", rows.join("; "))?;
+                for target in targets {
+                    write!(f, " q{}", target)?;
+                }
+                Ok(())
+            },
+            Instruction::Measure { qubit, bit } => write!(f, "measure q{} -> c{}", qubit, bit),
+            Instruction::Conditional { bit, value, instruction } => write!(f, "if({}c{}) {}", if *value { "" } else { "!" }, bit, instruction)
+        }
+    }
+}
+impl<T: Float> Instruction<T> {
+    // Every register the instruction touches; controls come before targets
+    pub fn qubits ( &self ) -> Vec<usize> {
+        match self {
+            Instruction::Gate { controls, targets, .. } => controls.iter().chain( targets ).copied().collect(),
+            Instruction::Unitary { targets, .. } => targets.clone(),
+            Instruction::Measure { qubit, .. } => vec![*qubit],
+            Instruction::Conditional { instruction, .. } => instruction.qubits()
+        }
+    }
+    // Every classical bit the instruction reads or writes
+    pub fn bits ( &self ) -> Vec<usize> {
+        match self {
+            Instruction::Gate { .. } | Instruction::Unitary { .. } => Vec::new(),
+            Instruction::Measure { bit, .. } => vec![*bit],
+            Instruction::Conditional { bit, instruction, .. } => {
+                let mut bits = vec![*bit];
+                bits.extend( instruction.bits().into_iter().filter(|other| other != bit) );
+                bits
+            }
+        }
+    }
+    // The name the System reports to its noise model when running this
+    pub fn name ( &self ) -> &'static str {
+        match self {
+            Instruction::Gate { operation, controls, .. } => match (operation, controls.len()) {
+                (operation, 0) => operation.name(),
+                (Operation::X, 1) => "CNOT",
+                (Operation::X, 2) => "CCNOT",
+                (Operation::SWAP, _) => "CSWAP",
+                (Operation::R_x(_), _) => "CR_x",
+                (Operation::R_y(_), _) => "CR_y",
+                (Operation::R_z(_), _) => "CR_z",
+                (Operation::R_1(_), _) => "CR_1",
+                _ => "controlled"
+            },
+            Instruction::Unitary { .. } => "apply",
+            Instruction::Measure { .. } => "measure",
+            Instruction::Conditional { instruction, .. } => instruction.name()
+        }
+    }
+
+    fn validate ( &self ) -> Result<()> {
+        let qubits = self.qubits();
+        for (i, qubit) in qubits.iter().enumerate() {
+            if qubits[..i].contains( qubit ) {
+                return Err(QuantumError::DuplicateQubit( *qubit ));
+            }
+        }
+
+        match self {
+            Instruction::Gate { operation, targets, .. } => {
+                if targets.len() != operation.width() {
+                    return Err(QuantumError::MalformedCircuit(format!("{} acts on {} register(s), but was given {}", operation.name(), operation.width(), targets.len())));
+                }
+            },
+            Instruction::Unitary { matrix, targets } => {
+                if targets.is_empty() {
+                    return Err(QuantumError::MalformedCircuit("A unitary needs at least one target".to_string()));
+                }
+                let size = 1 << targets.len();
+                if matrix.rows != size || matrix.cols != size {
+                    return Err(QuantumError::DimensionMismatch { expected: (size, size), found: (matrix.rows, matrix.cols) });
+                }
+                if !matrix.unitary() {
+                    return Err(QuantumError::NonUnitaryGate);
+                }
+            },
+            Instruction::Measure { .. } => {},
+            Instruction::Conditional { instruction, .. } => instruction.validate()?
+        }
+        Ok(())
+    }
+    fn execute ( &self, system: &mut System<T>, bits: &mut [bool] ) -> Result<()> {
+        match self {
+            Instruction::Gate { operation, controls, targets } => apply_operation( system, operation, controls, targets ),
+            Instruction::Unitary { matrix, targets } => system.apply( matrix, targets ),
+            Instruction::Measure { qubit, bit } => {
+                bits[*bit] = system.measure( *qubit )?;
+                Ok(())
+            },
+            Instruction::Conditional { bit, value, instruction } => {
+                if bits[*bit] != *value {
+                    return Ok(());
+                }
+                instruction.execute( system, bits )
+            }
+        }
+    }
+    // Register i becomes qubits[i]
+    fn remap ( &self, qubits: &[usize] ) -> Self {
+        let map = |indices: &[usize]| indices.iter().map(|&index| qubits[index]).collect();
+        match self {
+            Instruction::Gate { operation, controls, targets } => Instruction::Gate {
+                operation: operation.clone(),
+                controls: map( controls ),
+                targets: map( targets )
+            },
+            Instruction::Unitary { matrix, targets } => Instruction::Unitary {
+                matrix: matrix.clone(),
+                targets: map( targets )
+            },
+            Instruction::Measure { qubit, bit } => Instruction::Measure {
+                qubit: qubits[*qubit],
+                bit: *bit
+            },
+            Instruction::Conditional { bit, value, instruction } => Instruction::Conditional {
+                bit: *bit,
+                value: *value,
+                instruction: Box::new(instruction.remap( qubits ))
+            }
+        }
+    }
+    fn symbol ( &self ) -> Option<&str> {
+        match self {
+            Instruction::Gate { operation, .. } => match operation.parameter() {
+                Some(Parameter::Symbol(name)) => Some(name),
+                _ => None
+            },
+            Instruction::Conditional { instruction, .. } => instruction.symbol(),
+            Instruction::Unitary { .. } | Instruction::Measure { .. } => None
+        }
+    }
+    fn bind ( &self, values: &[(&str, T)] ) -> Self {
+        match self {
+            Instruction::Gate { operation, controls, targets } => {
+                let value = self.symbol().and_then(|name| values.iter().find(|(symbol, _)| *symbol == name));
+                let operation = match value {
+                    Some(&(_, value)) => operation.with_parameter( Parameter::Value(value) ),
+                    None => operation.clone()
+                };
+                Instruction::Gate { operation, controls: controls.clone(), targets: targets.clone() }
+            },
+            Instruction::Conditional { bit, value, instruction } => Instruction::Conditional {
+                bit: *bit,
+                value: *value,
+                instruction: Box::new(instruction.bind( values ))
+            },
+            Instruction::Unitary { .. } | Instruction::Measure { .. } => self.clone()
+        }
+    }
+    fn inverse ( &self ) -> Result<Self> {
+        match self {
+            Instruction::Gate { operation, controls, targets } => Ok(Instruction::Gate {
+                operation: operation.inverse()?,
+                controls: controls.clone(),
+                targets: targets.clone()
+            }),
+            Instruction::Unitary { matrix, targets } => {
+                let mut matrix = matrix.clone();
+                matrix.adjunct();
+                Ok(Instruction::Unitary { matrix, targets: targets.clone() })
+            },
+            Instruction::Measure { .. } | Instruction::Conditional { .. } => Err(QuantumError::IrreversibleInstruction)
+        }
+    }
+    // I, and a rotation by exactly zero, do nothing
+    fn is_identity ( &self ) -> bool {
+        match self {
+            Instruction::Gate { operation, .. } => *operation == Operation::I || operation.parameter() == Some(&Parameter::Value(T::zero())),
+            _ => false
+        }
+    }
+    // What this instruction and `next`, directly after it on the same
+    // registers, reduce to: a gate followed by its inverse cancels, and
+    // rotations about the same axis add their angles
+    fn fuse ( &self, next: &Self ) -> Option<Fusion<T>> {
+        let (
+            Instruction::Gate { operation, controls, targets },
+            Instruction::Gate { operation: next_operation, controls: next_controls, targets: next_targets }
+        ) = (self, next) else { return None };
+        if controls != next_controls || targets != next_targets {
+            return None;
+        }
+
+        if operation.parameter().is_none() && operation.inverse().as_ref() == Ok(next_operation) {
+            return Some(Fusion::Cancel);
+        }
+        let (Some(Parameter::Value(first)), Some(Parameter::Value(second))) = (operation.parameter(), next_operation.parameter()) else { return None };
+        if operation.name() != next_operation.name() {
+            return None;
+        }
+
+        let merged = Instruction::Gate {
+            operation: operation.with_parameter( Parameter::Value(*first + *second) ),
+            controls: controls.clone(),
+            targets: targets.clone()
+        };
+        if merged.is_identity() {
+            return Some(Fusion::Cancel);
+        }
+        Some(Fusion::Merged(merged))
+    }
+}
+enum Fusion<T: Float> {
+    Cancel,
+    Merged(Instruction<T>)
+}
+
+// Runs a named gate through the System method of the same name, so that the
+// noise model sees the same gate names as it would for hand-written code
+fn apply_operation<T: Float> ( system: &mut System<T>, operation: &Operation<T>, controls: &[usize], targets: &[usize] ) -> Result<()> {
+    match (operation, controls, targets) {
+        (Operation::SWAP, [], &[register_1, register_2]) => system.SWAP( register_1, register_2 ),
+        (Operation::SWAP, &[control], &[register_1, register_2]) => system.CSWAP( control, register_1, register_2 ),
+        (Operation::SWAP, controls, &[register_1, register_2]) => system.controlled_swap( "CSWAP", controls, register_1, register_2 ),
+        (Operation::X, &[control], &[target]) => system.CNOT( control, target ),
+        (Operation::X, &[control_1, control_2], &[target]) => system.CCNOT( control_1, control_2, target ),
+        (operation, [], &[target]) => {
+            let mut qubit = system.qubit( target )?;
+            match operation {
+                Operation::I => qubit.I(),
+                Operation::X => qubit.X(),
+                Operation::Y => qubit.Y(),
+                Operation::Z => qubit.Z(),
+                Operation::H => qubit.H(),
+                Operation::S => qubit.S(),
+                Operation::T => qubit.T(),
+                Operation::S_adj => qubit.S_adj(),
+                Operation::T_adj => qubit.T_adj(),
+                Operation::R_x(theta) => qubit.R_x( theta.value()? ),
+                Operation::R_y(theta) => qubit.R_y( theta.value()? ),
+                Operation::R_z(theta) => qubit.R_z( theta.value()? ),
+                Operation::R_1(theta) => qubit.R_1( theta.value()? ),
+                Operation::SWAP => unreachable!("SWAP always has two targets")
+            };
+            Ok(())
+        },
+        (Operation::R_x(theta), controls, &[target]) => system.CR_x( controls, target, theta.value()? ),
+        (Operation::R_y(theta), controls, &[target]) => system.CR_y( controls, target, theta.value()? ),
+        (Operation::R_z(theta), controls, &[target]) => system.CR_z( controls, target, theta.value()? ),
+        (Operation::R_1(theta), controls, &[target]) => system.CR_1( controls, target, theta.value()? ),
+        (operation, controls, &[target]) => system.controlled( controls, target, &operation.matrix()? ),
+        (operation, _, targets) => Err(QuantumError::MalformedCircuit(format!("{} acts on {} register(s), but was given {}", operation.name(), operation.width(), targets.len())))
+    }
+}
+
+// An ordered list of instructions over `qubits` registers and `bits`
+// classical bits. Rather than acting on a System as it is written, a circuit
+// is built first, can then be inspected, transformed and serialized, and runs
+// on any System afterwards, as many times as needed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Circuit<T: Float = f64> {
+    instructions: Vec<Instruction<T>>,
+    qubits: usize,
+    bits: usize
+}
+impl<T: Float> fmt::Display for Circuit<T> {
+    fn fmt ( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        writeln!(f, "qubits {}", self.qubits)?;
+        writeln!(f, "bits {}", self.bits)?;
+        for instruction in &self.instructions {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+impl<T: Float> FromStr for Circuit<T> {
+    type Err = QuantumError;
+
+    fn from_str ( text: &str ) -> Result<Self> {
+        Self::parse( text )
+    }
+}
+impl<T: Float> Circuit<T> {
+    pub fn new ( qubits: usize, bits: usize ) -> Self {
+        Self {
+            instructions: Vec::new(),
+            qubits,
+            bits
+        }
+    }
+    pub fn qubits ( &self ) -> usize {
+        self.qubits
+    }
+    pub fn bits ( &self ) -> usize {
+        self.bits
+    }
+    pub fn instructions ( &self ) -> &[Instruction<T>] {
+        &self.instructions
+    }
+    pub fn len ( &self ) -> usize {
+        self.instructions.len()
+    }
+    pub fn is_empty ( &self ) -> bool {
+        self.instructions.is_empty()
+    }
+
+    /* Building */
+    // Appends an instruction, widening the circuit to cover the registers
+    // and bits it uses
+    pub fn push ( &mut self, instruction: Instruction<T> ) -> Result<&mut Self> {
+        instruction.validate()?;
+
+        if let Some(&qubit) = instruction.qubits().iter().max() {
+            self.qubits = self.qubits.max( qubit + 1 );
+        }
+        if let Some(&bit) = instruction.bits().iter().max() {
+            self.bits = self.bits.max( bit + 1 );
+        }
+        self.instructions.push( instruction );
+        Ok(self)
+    }
+    // Appends `other`, its register i landing on qubits[i]. Classical bits
+    // are shared as they are.
+    pub fn append ( &mut self, other: &Self, qubits: &[usize] ) -> Result<&mut Self> {
+        if qubits.len() != other.qubits {
+            return Err(QuantumError::MalformedCircuit(format!("Circuit of {} qubits was given {} registers to land on", other.qubits, qubits.len())));
+        }
+        for (i, qubit) in qubits.iter().enumerate() {
+            if qubits[..i].contains( qubit ) {
+                return Err(QuantumError::DuplicateQubit( *qubit ));
+            }
+        }
+
+        for instruction in &other.instructions {
+            self.push( instruction.remap( qubits ) )?;
+        }
+        self.bits = self.bits.max( other.bits );
+        Ok(self)
+    }
+    // Applies a named gate to `targets` where every control is |1>
+    pub fn controlled ( &mut self, controls: &[usize], targets: &[usize], operation: Operation<T> ) -> Result<&mut Self> {
+        self.push(Instruction::Gate {
+            operation,
+            controls: controls.to_vec(),
+            targets: targets.to_vec()
+        })
+    }
+    // Applies a 2^k x 2^k unitary to the k registers in `targets`
+    pub fn apply ( &mut self, matrix: Matrix<T>, targets: &[usize] ) -> Result<&mut Self> {
+        self.push(Instruction::Unitary {
+            matrix,
+            targets: targets.to_vec()
+        })
+    }
+    pub fn measure ( &mut self, qubit: usize, bit: usize ) -> &mut Self {
+        self.push( Instruction::Measure { qubit, bit } ).expect("A measurement has a single register")
+    }
+    // Runs `instruction` only if classical bit `bit` was measured as `value`
+    pub fn conditional ( &mut self, bit: usize, value: bool, instruction: Instruction<T> ) -> Result<&mut Self> {
+        self.push(Instruction::Conditional {
+            bit,
+            value,
+            instruction: Box::new(instruction)
+        })
+    }
+    fn single ( &mut self, operation: Operation<T>, target: usize ) -> &mut Self {
+        self.controlled( &[], &[target], operation ).expect("A single-qubit gate has a single register")
+    }
+
+    /* - Single-qubit gates - */
+    #[allow(non_snake_case)]
+    pub fn I ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::I, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn X ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::X, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn Y ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::Y, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn Z ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::Z, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn H ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::H, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn S ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::S, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn T ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::T, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn S_adj ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::S_adj, target )
+    }
+    #[allow(non_snake_case)]
+    pub fn T_adj ( &mut self, target: usize ) -> &mut Self {
+        self.single( Operation::T_adj, target )
+    }
+    // Rotations take either an angle or the name of a symbol, e.g.
+    // circuit.R_x( 0, 0.5 ) or circuit.R_x( 0, "theta" )
+    #[allow(non_snake_case)]
+    pub fn R_x ( &mut self, target: usize, theta: impl Into<Parameter<T>> ) -> &mut Self {
+        self.single( Operation::R_x(theta.into()), target )
+    }
+    #[allow(non_snake_case)]
+    pub fn R_y ( &mut self, target: usize, theta: impl Into<Parameter<T>> ) -> &mut Self {
+        self.single( Operation::R_y(theta.into()), target )
+    }
+    #[allow(non_snake_case)]
+    pub fn R_z ( &mut self, target: usize, theta: impl Into<Parameter<T>> ) -> &mut Self {
+        self.single( Operation::R_z(theta.into()), target )
+    }
+    #[allow(non_snake_case)]
+    pub fn R_1 ( &mut self, target: usize, theta: impl Into<Parameter<T>> ) -> &mut Self {
+        self.single( Operation::R_1(theta.into()), target )
+    }
+
+    /* - Multi-qubit gates - */
+    #[allow(non_snake_case)]
+    pub fn SWAP ( &mut self, register_1_ind: usize, register_2_ind: usize ) -> Result<&mut Self> {
+        self.controlled( &[], &[register_1_ind, register_2_ind], Operation::SWAP )
+    }
+    #[allow(non_snake_case)]
+    pub fn CNOT ( &mut self, register_1_ind: usize, register_2_ind: usize ) -> Result<&mut Self> {
+        self.controlled( &[register_1_ind], &[register_2_ind], Operation::X )
+    }
+    #[allow(non_snake_case)]
+    pub fn CCNOT ( &mut self, control_1_ind: usize, control_2_ind: usize, target_ind: usize ) -> Result<&mut Self> {
+        self.controlled( &[control_1_ind, control_2_ind], &[target_ind], Operation::X )
+    }
+    #[allow(non_snake_case)]
+    pub fn CSWAP ( &mut self, control_ind: usize, register_1_ind: usize, register_2_ind: usize ) -> Result<&mut Self> {
+        self.controlled( &[control_ind], &[register_1_ind, register_2_ind], Operation::SWAP )
+    }
+    #[allow(non_snake_case)]
+    pub fn CR_x ( &mut self, controls: &[usize], target: usize, theta: impl Into<Parameter<T>> ) -> Result<&mut Self> {
+        self.controlled( controls, &[target], Operation::R_x(theta.into()) )
+    }
+    #[allow(non_snake_case)]
+    pub fn CR_y ( &mut self, controls: &[usize], target: usize, theta: impl Into<Parameter<T>> ) -> Result<&mut Self> {
+        self.controlled( controls, &[target], Operation::R_y(theta.into()) )
+    }
+    #[allow(non_snake_case)]
+    pub fn CR_z ( &mut self, controls: &[usize], target: usize, theta: impl Into<Parameter<T>> ) -> Result<&mut Self> {
+        self.controlled( controls, &[target], Operation::R_z(theta.into()) )
+    }
+    #[allow(non_snake_case)]
+    pub fn CR_1 ( &mut self, controls: &[usize], target: usize, theta: impl Into<Parameter<T>> ) -> Result<&mut Self> {
+        self.controlled( controls, &[target], Operation::R_1(theta.into()) )
+    }
+
+    /* Inspection */
+    // Number of layers when every instruction runs as early as the registers
+    // and classical bits it uses allow
+    pub fn depth ( &self ) -> usize {
+        let mut qubit_layers = vec![0usize; self.qubits];
+        let mut bit_layers = vec![0usize; self.bits];
+
+        let mut depth = 0;
+        for instruction in &self.instructions {
+            let qubits = instruction.qubits();
+            let bits = instruction.bits();
+            let layer = 1 + qubits.iter().map(|&qubit| qubit_layers[qubit])
+                .chain( bits.iter().map(|&bit| bit_layers[bit]) )
+                .max()
+                .unwrap_or( 0 );
+
+            for qubit in qubits {
+                qubit_layers[qubit] = layer;
+            }
+            for bit in bits {
+                bit_layers[bit] = layer;
+            }
+            depth = depth.max( layer );
+        }
+        depth
+    }
+    // How many times each gate appears, by the name the System runs it under
+    pub fn gate_counts ( &self ) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for instruction in &self.instructions {
+            *counts.entry( instruction.name() ).or_insert( 0 ) += 1;
+        }
+        counts
+    }
+    // Names of the parameters still waiting to be bound
+    pub fn symbols ( &self ) -> BTreeSet<String> {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| instruction.symbol())
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /* Transformations */
+    // A copy with every symbol named in `values` replaced by its value;
+    // symbols left out stay as they are
+    pub fn bind ( &self, values: &[(&str, T)] ) -> Self {
+        Self {
+            instructions: self.instructions.iter().map(|instruction| instruction.bind( values )).collect(),
+            ..self.clone()
+        }
+    }
+    // The circuit undoing this one: every gate inverted, in reverse order.
+    // Measurements can't be undone, and rotations need bound angles.
+    pub fn inverse ( &self ) -> Result<Self> {
+        Ok(Self {
+            instructions: self.instructions.iter().rev().map(|instruction| instruction.inverse()).collect::<Result<_>>()?,
+            ..self.clone()
+        })
+    }
+    // Peephole optimization: drops I and zero-angle rotations, cancels gates
+    // against their inverses (X X, S S_adj, ...) and merges rotations about
+    // the same axis, wherever nothing else acts on their registers in
+    // between. Repeats until no more apply, so e.g. H X X H vanishes
+    // entirely. Noise a model attaches to the dropped gates, such as idle
+    // noise on I, goes with them.
+    pub fn optimize ( &self ) -> Self {
+        let mut instructions: Vec<Instruction<T>> = self.instructions
+            .iter()
+            .filter(|instruction| !instruction.is_identity())
+            .cloned()
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            let mut i = 0;
+            while i < instructions.len() {
+                let qubits = instructions[i].qubits();
+                let next = (i + 1..instructions.len())
+                    .find(|&j| instructions[j].qubits().iter().any(|qubit| qubits.contains( qubit )));
+
+                match next.and_then(|j| instructions[i].fuse( &instructions[j] ).map(|fusion| (j, fusion))) {
+                    Some((j, Fusion::Cancel)) => {
+                        instructions.remove( j );
+                        instructions.remove( i );
+                        changed = true;
+                    },
+                    Some((j, Fusion::Merged(merged))) => {
+                        instructions.remove( j );
+                        instructions[i] = merged;
+                        changed = true;
+                    },
+                    None => i += 1
+                }
+            }
+        }
+
+        Self {
+            instructions,
+            ..self.clone()
+        }
+    }
+
+    /* Execution */
+    // Runs the circuit on `system`, register i of the circuit being register
+    // i of the system; registers the system doesn't have yet are allocated
+    // in |0>. Returns the classical bits, false until measured.
+    pub fn run ( &self, system: &mut System<T> ) -> Result<Vec<bool>> {
+        // Checked up front, so that a failure doesn't leave the system half-run
+        if let Some(name) = self.symbols().into_iter().next() {
+            return Err(QuantumError::UnboundParameter(name));
+        }
+
+        while system.qubits() < self.qubits {
+            system.allocate();
+        }
+
+        let mut bits = vec![false; self.bits];
+        for instruction in &self.instructions {
+            instruction.execute( system, &mut bits )?;
+        }
+        Ok(bits)
+    }
+
+    /* Serialization */
+    // Reads the text form that Display writes: a `qubits n` and `bits n`
+    // header, then one instruction per line, e.g.
+    //
+    //     H q0
+    //     ctrl(q0) X q1
+    //     R_z(theta) q1
+    //     unitary(0 0, 1 0; 1 0, 0 0) q2
+    //     measure q1 -> c0
+    //     if(c0) X q2
+    //
+    // Unitaries list each entry as 're im', with ',' between entries and ';'
+    // between rows. Blank lines and anything after '//' are ignored.
+    pub fn parse ( text: &str ) -> Result<Self> {
+        let mut circuit = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split("//").next().unwrap_or( "" ).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let malformed = |reason: String| QuantumError::MalformedCircuit(format!("line {}: {}", number + 1, reason));
+
+            if let Some(count) = line.strip_prefix("qubits ") {
+                circuit.qubits = circuit.qubits.max( parse_count( count ).map_err( malformed )? );
+            } else if let Some(count) = line.strip_prefix("bits ") {
+                circuit.bits = circuit.bits.max( parse_count( count ).map_err( malformed )? );
+            } else {
+                // Instructions that parse but don't validate are reported
+                // against their line too
+                circuit.push( parse_instruction( line ).map_err( malformed )? ).map_err(|error| match error {
+                    QuantumError::MalformedCircuit(reason) => malformed( reason ),
+                    error => malformed( error.to_string() )
+                })?;
+            }
+        }
+        Ok(circuit)
+    }
+}
+
+/* Parsing helpers, whose errors become MalformedCircuit with a line number */
+fn parse_instruction<T: Float> ( text: &str ) -> std::result::Result<Instruction<T>, String> {
+    if let Some(rest) = text.strip_prefix("if(") {
+        let (condition, rest) = rest.split_once(')').ok_or( "unclosed condition" )?;
+        let (value, bit) = match condition.trim().strip_prefix('!') {
+            Some(bit) => (false, bit),
+            None => (true, condition)
+        };
+        return Ok(Instruction::Conditional {
+            bit: parse_index( bit, 'c' )?,
+            value,
+            instruction: Box::new(parse_instruction( rest.trim() )?)
+        });
+    }
+    if let Some(rest) = text.strip_prefix("measure ") {
+        let (qubit, bit) = rest.split_once("->").ok_or( "expected 'measure q<n> -> c<n>'" )?;
+        return Ok(Instruction::Measure {
+            qubit: parse_index( qubit, 'q' )?,
+            bit: parse_index( bit, 'c' )?
+        });
+    }
+    if let Some(rest) = text.strip_prefix("unitary(") {
+        let (entries, targets) = rest.split_once(')').ok_or( "unclosed matrix" )?;
+        let rows = entries
+            .split(';')
+            .map(|row| row.split(',').map( parse_complex ).collect())
+            .collect::<std::result::Result<Vec<Vec<ComplexNumber<T>>>, String>>()?;
+        return Ok(Instruction::Unitary {
+            matrix: Matrix::new( rows ).map_err(|error| error.to_string())?,
+            targets: parse_operands( targets )?
+        });
+    }
+
+    let (controls, rest) = match text.strip_prefix("ctrl(") {
+        Some(rest) => {
+            let (controls, rest) = rest.split_once(')').ok_or( "unclosed controls" )?;
+            let controls = controls.split(',').map(|control| parse_index( control, 'q' )).collect::<std::result::Result<_, _>>()?;
+            (controls, rest.trim())
+        },
+        None => (Vec::new(), text)
+    };
+    let (head, operands) = rest.split_once(' ').unwrap_or( (rest, "") );
+    let (name, parameter) = match head.split_once('(') {
+        Some((name, parameter)) => {
+            let parameter = parameter.strip_suffix(')').ok_or( "unclosed parameter" )?;
+            (name, Some(parse_parameter( parameter )?))
+        },
+        None => (head, None)
+    };
+
+    Ok(Instruction::Gate {
+        operation: Operation::from_name( name, parameter ).ok_or_else(|| format!("unknown gate '{}'", head))?,
+        controls,
+        targets: parse_operands( operands )?
+    })
+}
+fn parse_count ( text: &str ) -> std::result::Result<usize, String> {
+    text.trim().parse().map_err(|_| format!("'{}' is not a count", text.trim()))
+}
+// 'q3' or 'c3', with `prefix` telling which
+fn parse_index ( text: &str, prefix: char ) -> std::result::Result<usize, String> {
+    let text = text.trim();
+    text.strip_prefix(prefix)
+        .and_then(|index| index.parse().ok())
+        .ok_or_else(|| format!("expected {}<n>, found '{}'", prefix, text))
+}
+fn parse_operands ( text: &str ) -> std::result::Result<Vec<usize>, String> {
+    text.split_whitespace().map(|operand| parse_index( operand, 'q' )).collect()
+}
+// A number if it starts like one, so that symbols named e.g. inf or nan
+// aren't read as floats; the angle must then be finite
+fn parse_parameter<T: Float> ( text: &str ) -> std::result::Result<Parameter<T>, String> {
+    let text = text.trim();
+    if text.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '+' | '-' | '.')) {
+        return Ok(Parameter::Value(T::from_f64( parse_finite( text )? )));
+    }
+
+    let identifier = text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !identifier {
+        return Err(format!("'{}' is neither a number nor a symbol name", text));
+    }
+    Ok(Parameter::Symbol(text.to_string()))
+}
+fn parse_complex<T: Float> ( text: &str ) -> std::result::Result<ComplexNumber<T>, String> {
+    let parts: Vec<f64> = text
+        .split_whitespace()
+        .map( parse_finite )
+        .collect::<std::result::Result<_, _>>()?;
+    let &[a, b] = parts.as_slice() else {
+        return Err(format!("expected 're im', found '{}'", text.trim()));
+    };
+    Ok(ComplexNumber::new( T::from_f64( a ), T::from_f64( b ) ))
+}
+fn parse_finite ( text: &str ) -> std::result::Result<f64, String> {
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        Ok(_) => Err(format!("'{}' is not finite", text)),
+        Err(_) => Err(format!("'{}' is not a number", text))
+    }
+}
@@ -1,8 +1,15 @@
+use crate::error::{
+    QuantumError,
+    Result
+};
+use crate::float::Float;
+
 use std::ops::{
     Add,
     Sub,
     Mul,
     Div,
+    Neg,
 
     AddAssign,
     SubAssign,
@@ -10,55 +17,63 @@ use std::ops::{
     DivAssign
 };
 
-#[derive(Clone, PartialEq)]
-pub struct ComplexNumber {
-	pub a: f32,
-    pub b: f32
+#[derive(Clone, Copy, PartialEq)]
+pub struct ComplexNumber<T: Float = f64> {
+	pub a: T,
+    pub b: T
 }
-impl std::fmt::Debug for ComplexNumber {
+impl<T: Float> std::fmt::Debug for ComplexNumber<T> {
     fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
-        let operator: &str = if self.b.signum() == 1.0 { "+" } else { "-" };
+        let operator: &str = if self.b.signum() == T::one() { "+" } else { "-" };
 
         write!(f, "({} {} {}i)", self.a, operator, self.b.abs())?;
         Ok(())
     }
 }
-impl Add for ComplexNumber {
+impl<T: Float> Add for ComplexNumber<T> {
     type Output = Self;
 
     fn add ( self, to_add: Self ) -> Self {
         Self { a: self.a + to_add.a, b: self.b + to_add.b }
     }
 }
-impl AddAssign for ComplexNumber {
+impl<T: Float> AddAssign for ComplexNumber<T> {
     fn add_assign ( &mut self, to_add: Self ) {
         self.a += to_add.a;
         self.b += to_add.b;
     }
 }
-impl Sub for ComplexNumber {
+impl<T: Float> Sub for ComplexNumber<T> {
     type Output = Self;
 
     fn sub ( self, to_sub: Self ) -> Self {
         Self { a: self.a - to_sub.a, b: self.b - to_sub.b }
     }
 }
-impl SubAssign for ComplexNumber {
+impl<T: Float> SubAssign for ComplexNumber<T> {
     fn sub_assign ( &mut self, to_sub: Self ) {
         self.a -= to_sub.a;
         self.b -= to_sub.b;
     }
 }
-impl Mul<f32> for ComplexNumber {
+impl<T: Float> Neg for ComplexNumber<T> {
     type Output = Self;
 
-    fn mul ( self, to_mul: f32 ) -> Self {
+    fn neg ( self ) -> Self {
+        Self { a: -self.a, b: -self.b }
+    }
+}
+impl<T: Float> Mul<T> for ComplexNumber<T> {
+    type Output = Self;
+
+    fn mul ( self, to_mul: T ) -> Self {
         Self { a: self.a * to_mul, b: self.b * to_mul }
     }
 }
-impl Mul<ComplexNumber> for ComplexNumber{
+impl<T: Float> Mul<ComplexNumber<T>> for ComplexNumber<T> {
     type Output = Self;
 
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn mul ( self, to_mul: Self ) -> Self {
         let a = self.a;
         let b = self.b;
@@ -71,14 +86,15 @@ impl Mul<ComplexNumber> for ComplexNumber{
         }
     }
 }
-impl MulAssign<f32> for ComplexNumber {
-    fn mul_assign ( &mut self, to_mul: f32 ) {
+impl<T: Float> MulAssign<T> for ComplexNumber<T> {
+    fn mul_assign ( &mut self, to_mul: T ) {
         self.a *= to_mul;
         self.b *= to_mul;
     }
 }
-impl MulAssign<ComplexNumber> for ComplexNumber {
-    fn mul_assign ( &mut self, to_mul: ComplexNumber ) {
+impl<T: Float> MulAssign<ComplexNumber<T>> for ComplexNumber<T> {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn mul_assign ( &mut self, to_mul: ComplexNumber<T> ) {
         let a = self.a;
         let b = self.b;
         let c = to_mul.a;
@@ -88,68 +104,110 @@ impl MulAssign<ComplexNumber> for ComplexNumber {
         self.b = b * c + a * d;
     }
 }
-impl Div<f32> for ComplexNumber {
+impl<T: Float> Div<T> for ComplexNumber<T> {
     type Output = Self;
 
-    fn div ( self, to_div: f32 ) -> Self {
+    fn div ( self, to_div: T ) -> Self {
         Self { a: self.a / to_div, b: self.b / to_div }
     }
 }
-impl Div<ComplexNumber> for ComplexNumber {
+impl<T: Float> Div<ComplexNumber<T>> for ComplexNumber<T> {
     type Output = Self;
 
-    fn div ( self, to_div: ComplexNumber ) -> Self {
-        if to_div.a == 0f32 && to_div.b == 0f32 {
-            panic!("Denominator cannot be zero!");
-        }
-
+    // Like float division, a zero denominator yields infinities or NaN.
+    // Use checked_div() to catch it instead.
+    fn div ( self, to_div: ComplexNumber<T> ) -> Self {
         let a = self.a;
         let b = self.b;
         let c = to_div.a;
         let d = to_div.b;
-    
+
         Self {
             a: ( a * c + b * d ) / ( c.powi(2) + d.powi(2) ),
             b: ( b * c - a * d) / ( c.powi(2) + d.powi(2) )
         }
     }
 }
-impl DivAssign<f32> for ComplexNumber {
-    fn div_assign ( &mut self, to_div: f32 ) {
+impl<T: Float> DivAssign<T> for ComplexNumber<T> {
+    fn div_assign ( &mut self, to_div: T ) {
         self.a /= to_div;
         self.b /= to_div;
     }
 }
-impl DivAssign<ComplexNumber> for ComplexNumber {
-    fn div_assign ( &mut self, to_div: ComplexNumber ) {
-        if to_div.a == 0f32 && to_div.b == 0f32 {
-            panic!("Denominator cannot be zero!");
+impl<T: Float> DivAssign<ComplexNumber<T>> for ComplexNumber<T> {
+    fn div_assign ( &mut self, to_div: ComplexNumber<T> ) {
+        *self = *self / to_div;
+    }
+}
+impl<T: Float> ComplexNumber<T> {
+    pub fn new ( a: T, b: T ) -> Self {
+        Self { a, b }
+    }
+    pub fn zero () -> Self {
+        Self::new( T::zero(), T::zero() )
+    }
+    pub fn one () -> Self {
+        Self::new( T::one(), T::zero() )
+    }
+    pub fn i () -> Self {
+        Self::new( T::zero(), T::one() )
+    }
+
+    // Equal to within an absolute distance of eps
+    pub fn approx_eq ( &self, other: &Self, eps: T ) -> bool {
+        ( *self - *other ).modulus() <= eps
+    }
+    // Equal to within eps relative to the larger modulus. Near zero this
+    // falls back to an absolute tolerance of eps, so 1e-17 still equals 0.
+    pub fn relative_eq ( &self, other: &Self, eps: T ) -> bool {
+        let scale = self.modulus().max( other.modulus() ).max( T::one() );
+
+        ( *self - *other ).modulus() <= eps * scale
+    }
+
+    pub fn checked_div ( self, to_div: ComplexNumber<T> ) -> Result<Self> {
+        if to_div.a == T::zero() && to_div.b == T::zero() {
+            return Err(QuantumError::DivisionByZero);
         }
 
-        let a = self.a;
-        let b = self.b;
-        let c = to_div.a;
-        let d = to_div.b;
-    
-        self.a = ( a * c + b * d ) / ( c.powi(2) + d.powi(2) );
-        self.b = ( b * c - a * d) / ( c.powi(2) + d.powi(2) );
+        Ok(self / to_div)
     }
-}
-impl ComplexNumber {
     pub fn conjugate ( &mut self ) -> &Self {
-        self.b *= -1f32;
+        self.b = -self.b;
         self
     }
-    pub fn modulus ( &self ) -> f32 {
+    // The complex conjugate as a new value, for use inside expressions
+    pub fn conj ( self ) -> Self {
+        Self::new( self.a, -self.b )
+    }
+    pub fn modulus ( &self ) -> T {
         ( self.a.powi(2) + self.b.powi(2) ).sqrt()
     }
+    // Squared modulus, the Born-rule probability of an amplitude
+    pub fn norm_sqr ( &self ) -> T {
+        self.a.powi(2) + self.b.powi(2)
+    }
     pub fn exp ( &mut self ) -> &Self {
-        self.a = self.a.exp() * self.b.cos();
-        self.b = self.a.exp() * self.b.sin();
+        let magnitude = self.a.exp();
+        let angle = self.b;
+
+        self.a = magnitude * angle.cos();
+        self.b = magnitude * angle.sin();
+
+        self
+    }
+    // Principal natural logarithm, with imaginary part in (-π, π]
+    pub fn ln ( &mut self ) -> &Self {
+        // atan2 would put a -0 imaginary part at -π
+        let b = if self.b == T::zero() { T::zero() } else { self.b };
+        let theta = b.atan2( self.a );
+
+        self.a = self.modulus().ln();
+        self.b = theta;
 
         self
     }
-    pub fn polar ( &self ) -> ComplexPolarNumber {
+    pub fn polar ( &self ) -> ComplexPolarNumber<T> {
         let r = self.modulus();
         let theta = ( self.b ).atan2( self.a );
         ComplexPolarNumber {
@@ -157,16 +215,16 @@ impl ComplexNumber {
             theta
         }
     }
-    pub fn arbitrary_exp ( &mut self, to_exp: ComplexNumber ) -> &Self {
+    pub fn arbitrary_exp ( &mut self, to_exp: ComplexNumber<T> ) -> &Self {
         let base_as_polar = self.polar();
 
         let r = base_as_polar.r;
         let theta = base_as_polar.theta;
 
-        if r == 0f32 {
+        if r == T::zero() {
             // prolly unnessecary but i'm tired
-            self.a = 0f32;
-            self.b = 0f32;
+            self.a = T::zero();
+            self.b = T::zero();
 
             return self;
         }
@@ -181,34 +239,34 @@ impl ComplexNumber {
     }
 }
 
-#[derive(Clone)]
-pub struct ComplexPolarNumber {
-    pub r: f32,
-    pub theta: f32
+#[derive(Clone, Copy)]
+pub struct ComplexPolarNumber<T: Float = f64> {
+    pub r: T,
+    pub theta: T
 }
-impl std::fmt::Debug for ComplexPolarNumber {
+impl<T: Float> std::fmt::Debug for ComplexPolarNumber<T> {
     fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
         write!(f, "({} units, {} rads)", self.r, self.theta )?;
 
         Ok(())
     }
 }
-impl Mul<ComplexPolarNumber> for ComplexPolarNumber {
+impl<T: Float> Mul<ComplexPolarNumber<T>> for ComplexPolarNumber<T> {
     type Output = Self;
-    
+
     fn mul ( self, to_mul: Self ) -> Self {
         let mut r     = self.r * to_mul.r;
         let mut theta = self.theta + to_mul.theta;
-    
-        if r < 0f32 {
-            r *= -1f32;
-            theta += std::f32::consts::PI;
+
+        if r < T::zero() {
+            r = -r;
+            theta += T::pi();
         }
-        while theta > std::f32::consts::PI {
-            theta -= std::f32::consts::TAU;
+        while theta > T::pi() {
+            theta -= T::tau();
         }
-        while theta < -std::f32::consts::PI {
-            theta += std::f32::consts::TAU;
+        while theta < -T::pi() {
+            theta += T::tau();
         }
 
         Self {
@@ -217,8 +275,8 @@ impl Mul<ComplexPolarNumber> for ComplexPolarNumber {
         }
     }
 }
-impl ComplexPolarNumber {
-    pub fn cartesian( self ) -> ComplexNumber {
+impl<T: Float> ComplexPolarNumber<T> {
+    pub fn cartesian( self ) -> ComplexNumber<T> {
         let a = self.r * self.theta.cos();
         let b = self.r * self.theta.sin();
         ComplexNumber {
@@ -0,0 +1,72 @@
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuantumError {
+    // Rows of differing lengths, or no rows or columns at all
+    MalformedMatrix,
+    // Operand dimensions (rows, cols) don't fit the operation
+    DimensionMismatch {
+        expected: (usize, usize),
+        found: (usize, usize)
+    },
+    UnknownState(String),
+    QubitOutOfRange {
+        index: usize,
+        qubits: usize
+    },
+    // The same register was used twice where distinct registers are required
+    DuplicateQubit(usize),
+    NonUnitaryGate,
+    // A Hermitian-only routine was handed a matrix that isn't its own adjoint
+    NonHermitianMatrix,
+    // A routine for normal matrices (A A† = A† A) was handed something else
+    NonNormalMatrix,
+    // eigenvector_from_eigenvalue was given a value outside the spectrum
+    NotAnEigenvalue,
+    // Kraus operators that don't satisfy Σ K†K = I
+    IncompleteKraus,
+    // A probability outside of [0, 1]
+    InvalidProbability(f64),
+    // A state vector was asked of a mixed density matrix
+    MixedState,
+    // A state whose trace (for a ket, squared norm) isn't 1
+    UnnormalizedState(f64),
+    SingularMatrix,
+    // An iterative decomposition ran out of sweeps or QR steps before
+    // converging, so its result can't be trusted
+    NoConvergence,
+    DivisionByZero,
+    // A circuit instruction with the wrong operands, or a line of a
+    // serialized circuit that doesn't parse
+    MalformedCircuit(String),
+    // A circuit ran with a symbolic parameter that was never bound
+    UnboundParameter(String),
+    // A circuit with measurements has no inverse
+    IrreversibleInstruction
+}
+impl std::fmt::Display for QuantumError {
+    fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+        match self {
+            QuantumError::MalformedMatrix => write!(f, "Matrix is not two-dimensional! Ensure there is at least one row, at least one column and all rows are equal in length."),
+            QuantumError::DimensionMismatch { expected, found } => write!(f, "Expected a {}x{} matrix, found {}x{}", expected.0, expected.1, found.0, found.1),
+            QuantumError::UnknownState(id) => write!(f, "'{}' is not a valid or recognized ket state", id),
+            QuantumError::QubitOutOfRange { index, qubits } => write!(f, "Register {} does not exist in a system of {} qubits", index, qubits),
+            QuantumError::DuplicateQubit(index) => write!(f, "Register {} is used more than once", index),
+            QuantumError::NonUnitaryGate => write!(f, "Gate is not unitary"),
+            QuantumError::NonHermitianMatrix => write!(f, "Matrix is not Hermitian"),
+            QuantumError::NonNormalMatrix => write!(f, "Matrix is not normal"),
+            QuantumError::NotAnEigenvalue => write!(f, "Value is not an eigenvalue of the matrix"),
+            QuantumError::IncompleteKraus => write!(f, "Kraus operators do not satisfy the completeness relation (sum of K†K must be I)"),
+            QuantumError::InvalidProbability(p) => write!(f, "{} is not a probability between 0 and 1", p),
+            QuantumError::MixedState => write!(f, "State is mixed and cannot be written as a state vector"),
+            QuantumError::UnnormalizedState(trace) => write!(f, "State has trace {} rather than 1", trace),
+            QuantumError::SingularMatrix => write!(f, "Matrix is singular"),
+            QuantumError::NoConvergence => write!(f, "Decomposition did not converge within its iteration limit"),
+            QuantumError::DivisionByZero => write!(f, "Denominator cannot be zero"),
+            QuantumError::MalformedCircuit(reason) => write!(f, "Malformed circuit: {}", reason),
+            QuantumError::UnboundParameter(name) => write!(f, "Parameter '{}' has no value; bind it before running the circuit", name),
+            QuantumError::IrreversibleInstruction => write!(f, "Measurements and the instructions conditioned on them cannot be inverted")
+        }
+    }
+}
+impl std::error::Error for QuantumError {}
+
+pub type Result<T> = std::result::Result<T, QuantumError>;
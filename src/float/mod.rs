@@ -0,0 +1,81 @@
+use std::ops::{
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Neg,
+
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign
+};
+
+// Floating point precision that ComplexNumber, Matrix and System are generic
+// over. Implemented for f32 and f64; f64 is the default everywhere.
+pub trait Float:
+    Copy + PartialEq + PartialOrd + Default + Send + Sync + 'static
+    + std::fmt::Debug + std::fmt::Display + std::iter::Sum
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+    + AddAssign + SubAssign + MulAssign + DivAssign
+{
+    fn zero () -> Self;
+    fn one () -> Self;
+    fn from_f64 ( value: f64 ) -> Self;
+    fn to_f64 ( self ) -> f64;
+
+    fn pi () -> Self;
+    fn tau () -> Self;
+    fn frac_1_sqrt_2 () -> Self;
+    fn epsilon () -> Self;
+    // Default tolerance for approximate comparisons, loose enough to absorb
+    // the rounding of a long chain of gates in this precision
+    fn tolerance () -> Self;
+
+    fn sqrt ( self ) -> Self;
+    fn abs ( self ) -> Self;
+    fn signum ( self ) -> Self;
+    fn powi ( self, exponent: i32 ) -> Self;
+    fn powf ( self, exponent: Self ) -> Self;
+    fn exp ( self ) -> Self;
+    fn ln ( self ) -> Self;
+    fn sin ( self ) -> Self;
+    fn cos ( self ) -> Self;
+    fn atan2 ( self, other: Self ) -> Self;
+    fn max ( self, other: Self ) -> Self;
+    fn min ( self, other: Self ) -> Self;
+    fn to_degrees ( self ) -> Self;
+}
+
+macro_rules! impl_float {
+    ( $t:ident, $tolerance:expr ) => {
+        impl Float for $t {
+            fn zero () -> Self { 0.0 }
+            fn one () -> Self { 1.0 }
+            fn from_f64 ( value: f64 ) -> Self { value as $t }
+            fn to_f64 ( self ) -> f64 { self as f64 }
+
+            fn pi () -> Self { std::$t::consts::PI }
+            fn tau () -> Self { std::$t::consts::TAU }
+            fn frac_1_sqrt_2 () -> Self { std::$t::consts::FRAC_1_SQRT_2 }
+            fn epsilon () -> Self { $t::EPSILON }
+            fn tolerance () -> Self { $tolerance }
+
+            fn sqrt ( self ) -> Self { $t::sqrt( self ) }
+            fn abs ( self ) -> Self { $t::abs( self ) }
+            fn signum ( self ) -> Self { $t::signum( self ) }
+            fn powi ( self, exponent: i32 ) -> Self { $t::powi( self, exponent ) }
+            fn powf ( self, exponent: Self ) -> Self { $t::powf( self, exponent ) }
+            fn exp ( self ) -> Self { $t::exp( self ) }
+            fn ln ( self ) -> Self { $t::ln( self ) }
+            fn sin ( self ) -> Self { $t::sin( self ) }
+            fn cos ( self ) -> Self { $t::cos( self ) }
+            fn atan2 ( self, other: Self ) -> Self { $t::atan2( self, other ) }
+            fn max ( self, other: Self ) -> Self { $t::max( self, other ) }
+            fn min ( self, other: Self ) -> Self { $t::min( self, other ) }
+            fn to_degrees ( self ) -> Self { $t::to_degrees( self ) }
+        }
+    };
+}
+impl_float!(f32, 1e-5);
+impl_float!(f64, 1e-10);
@@ -0,0 +1,44 @@
+pub mod circuit;
+pub mod complex;
+pub mod error;
+pub mod float;
+pub mod matrix;
+pub mod noise;
+pub mod qubit;
+pub mod random;
+pub mod sparse;
+pub mod system;
+
+pub use circuit::{
+    Circuit,
+    Instruction,
+    Operation,
+    Parameter
+};
+pub use complex::{
+    ComplexNumber,
+    ComplexPolarNumber
+};
+pub use error::QuantumError;
+pub use float::Float;
+pub use matrix::{
+    Matrix,
+    MatrixView,
+    Gate
+};
+pub use noise::{
+    Channel,
+    NoiseModel
+};
+pub use qubit::Qubit;
+pub use random::{
+    Rng,
+    SeededRng
+};
+pub use sparse::SparseMatrix;
+pub use system::{
+    System,
+    QubitRef,
+    Pauli,
+    Backend
+};
@@ -0,0 +1,364 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// Sweeps after which Jacobi gives up with NoConvergence; convergence is
+// quadratic, so a well-behaved matrix needs well under ten
+const MAX_SWEEPS: usize = 64;
+// QR steps allowed per eigenvalue before the Schur iteration gives up with
+// NoConvergence
+const MAX_QR_STEPS: usize = 100;
+
+impl<T: Float> Matrix<T> {
+    // Eigenvalues (ascending) and an orthonormal basis of eigenvectors, as
+    // the columns of the returned matrix, of a Hermitian matrix such as an
+    // observable or a Hamiltonian
+    pub fn hermitian_eigen ( &self ) -> Result<( Vec<T>, Self )> {
+        self.check_square()?;
+        let mut adjunct = self.clone();
+        adjunct.adjunct();
+        if !self.relative_eq( &adjunct, T::tolerance() ) {
+            return Err(QuantumError::NonHermitianMatrix);
+        }
+
+        self.jacobi_eigen()
+    }
+    // Eigenvalues and matching unit eigenvectors, as the columns of the
+    // returned matrix, of any square matrix. The eigenvalues come in no
+    // particular order; for a defective matrix, eigenvectors of a repeated
+    // eigenvalue may coincide.
+    pub fn eigen ( &self ) -> Result<( Vec<ComplexNumber<T>>, Self )> {
+        self.check_square()?;
+        let n = self.rows;
+
+        // A = Z S Z† with S upper triangular, whose diagonal is the spectrum
+        let (schur, z) = self.schur()?;
+        let eigenvalues: Vec<ComplexNumber<T>> = (0..n).map(|k| schur[k][k]).collect();
+
+        // Back substitution for (S - λ_k I) x = 0 with x_k = 1 and x_j = 0
+        // past k. A repeated eigenvalue makes a denominator vanish, so
+        // nudge it off zero instead.
+        let norm = schur.data
+            .iter()
+            .map(|entry| entry.modulus())
+            .fold(T::zero(), T::max);
+        let floor = T::epsilon() * norm.max( T::one() );
+
+        let mut eigenvectors = Self::from_dimensions( n, n );
+        for (k, &eigenvalue) in eigenvalues.iter().enumerate() {
+            let mut x = vec![ComplexNumber::zero(); n];
+            x[k] = ComplexNumber::one();
+            for i in (0..k).rev() {
+                let sum = (i + 1..=k).fold(ComplexNumber::zero(), |acc, j| acc + schur[i][j] * x[j]);
+                let mut denominator = schur[i][i] - eigenvalue;
+                if denominator.modulus() < floor {
+                    denominator = ComplexNumber::new( floor, T::zero() );
+                }
+                x[i] = -sum / denominator;
+            }
+
+            let mut column: Vec<ComplexNumber<T>> = (0..n)
+                .map(|r| (0..=k).fold(ComplexNumber::zero(), |acc, j| acc + z[r][j] * x[j]))
+                .collect();
+            let length = column.iter().map(|entry| entry.norm_sqr()).sum::<T>().sqrt();
+            for entry in column.iter_mut() {
+                *entry /= length;
+            }
+            for (r, entry) in column.into_iter().enumerate() {
+                eigenvectors[r][k] = entry;
+            }
+        }
+
+        Ok(( eigenvalues, eigenvectors ))
+    }
+
+    // Hermitian eigendecomposition without validating the input, for callers
+    // that already know it's square and Hermitian. Uses cyclic complex Jacobi
+    // rotations; only the Hermitian part is looked at.
+    pub(crate) fn jacobi_eigen ( &self ) -> Result<( Vec<T>, Self )> {
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut v = Self::identity( n );
+
+        let mut sweeps = 0;
+        while !is_diagonal( &a ) {
+            if sweeps == MAX_SWEEPS {
+                return Err(QuantumError::NoConvergence);
+            }
+            for p in 0..n {
+                for q in p + 1..n {
+                    rotate( &mut a, &mut v, p, q );
+                }
+            }
+            sweeps += 1;
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&x, &y| a[x][x].a.partial_cmp( &a[y][y].a ).unwrap_or( std::cmp::Ordering::Equal ));
+
+        let eigenvalues = order.iter().map(|&i| a[i][i].a).collect();
+        let mut eigenvectors = Self::from_dimensions( n, n );
+        for (column, &i) in order.iter().enumerate() {
+            for r in 0..n {
+                eigenvectors[r][column] = v[r][i];
+            }
+        }
+
+        Ok(( eigenvalues, eigenvectors ))
+    }
+    // f(A) = V f(Λ) V† for a Hermitian A, applying f to each eigenvalue
+    pub(crate) fn hermitian_map ( &self, f: impl Fn(T) -> T ) -> Result<Self> {
+        let (eigenvalues, eigenvectors) = self.jacobi_eigen()?;
+        let n = self.rows;
+
+        let mut ret = Self::from_dimensions( n, n );
+        for (k, &eigenvalue) in eigenvalues.iter().enumerate() {
+            let value = f( eigenvalue );
+            for r in 0..n {
+                for c in 0..n {
+                    let v_ck = eigenvectors[c][k];
+                    ret[r][c] += eigenvectors[r][k] * v_ck.conj() * value;
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    // Complex Schur form (S, Z) with A = Z S Z†: Householder reduction to
+    // upper Hessenberg, then QR steps with Wilkinson shifts, deflating an
+    // eigenvalue off the bottom of the active block whenever a subdiagonal
+    // entry becomes negligible
+    pub(crate) fn schur ( &self ) -> Result<( Self, Self )> {
+        let n = self.rows;
+        let mut h = self.clone();
+        let mut z = Self::identity( n );
+        hessenberg( &mut h, &mut z );
+
+        let mut hi = n.saturating_sub( 1 );
+        let mut steps = 0;
+        while hi > 0 {
+            // Start of the unreduced block ending at hi
+            let mut lo = hi;
+            while lo > 0 {
+                let scale = h[lo - 1][lo - 1].modulus() + h[lo][lo].modulus();
+                if h[lo][lo - 1].modulus() <= T::epsilon() * scale.max( T::epsilon() ) {
+                    h[lo][lo - 1] = ComplexNumber::zero();
+                    break;
+                }
+                lo -= 1;
+            }
+            if lo == hi {
+                hi -= 1;
+                steps = 0;
+                continue;
+            }
+            if steps == MAX_QR_STEPS {
+                return Err(QuantumError::NoConvergence);
+            }
+            steps += 1;
+
+            let shift = if steps % 10 == 0 {
+                // Exceptional shift to break cycles that Wilkinson can't
+                h[hi][hi] + ComplexNumber::new( h[hi][hi - 1].modulus(), T::zero() )
+            } else {
+                wilkinson_shift( &h, hi )
+            };
+            qr_step( &mut h, &mut z, lo, hi, shift );
+        }
+
+        // Clear the rounding left under the diagonal
+        for r in 1..n {
+            for c in 0..r {
+                h[r][c] = ComplexNumber::zero();
+            }
+        }
+        Ok(( h, z ))
+    }
+}
+
+// Whether the off-diagonal part of a is negligible next to the whole, the
+// point at which Jacobi has converged
+fn is_diagonal<T: Float> ( a: &Matrix<T> ) -> bool {
+    let n = a.rows;
+    let off_diagonal: T = (0..n)
+        .flat_map(|p| (0..n).filter(move |&q| q != p).map(move |q| (p, q)))
+        .map(|(p, q)| a[p][q].norm_sqr())
+        .sum();
+    let scale: T = (0..n).map(|p| a[p][p].norm_sqr()).sum::<T>() + off_diagonal;
+
+    off_diagonal <= T::epsilon() * T::epsilon() * scale
+}
+
+// Reduces h to upper Hessenberg form with Householder reflections
+// P = I - 2 v v†, applying h <- P h P and z <- z P
+fn hessenberg<T: Float> ( h: &mut Matrix<T>, z: &mut Matrix<T> ) {
+    let n = h.rows;
+    for k in 0..n.saturating_sub( 2 ) {
+        let mut v: Vec<ComplexNumber<T>> = (k + 1..n).map(|r| h[r][k]).collect();
+        let length = v.iter().map(|entry| entry.norm_sqr()).sum::<T>().sqrt();
+        if length == T::zero() {
+            continue;
+        }
+        // Reflect x onto -e^{i arg x_0} |x| e_1, avoiding cancellation
+        let head = v[0];
+        let phase = if head.modulus() == T::zero() { ComplexNumber::one() } else { head / head.modulus() };
+        v[0] += phase * length;
+        let v_length = v.iter().map(|entry| entry.norm_sqr()).sum::<T>().sqrt();
+        for entry in v.iter_mut() {
+            *entry /= v_length;
+        }
+
+        let two = T::from_f64( 2.0 );
+        for c in 0..n {
+            let dot = v.iter().enumerate().fold(ComplexNumber::zero(), |acc, (i, vi)| acc + vi.conj() * h[k + 1 + i][c]);
+            for (i, vi) in v.iter().enumerate() {
+                h[k + 1 + i][c] -= *vi * dot * two;
+            }
+        }
+        for m in [&mut *h, &mut *z] {
+            for r in 0..n {
+                let dot = v.iter().enumerate().fold(ComplexNumber::zero(), |acc, (i, vi)| acc + m[r][k + 1 + i] * *vi);
+                for (i, vi) in v.iter().enumerate() {
+                    m[r][k + 1 + i] -= dot * vi.conj() * two;
+                }
+            }
+        }
+    }
+}
+// Eigenvalue of the trailing 2x2 block of h[..=hi] closest to its corner
+fn wilkinson_shift<T: Float> ( h: &Matrix<T>, hi: usize ) -> ComplexNumber<T> {
+    let a = h[hi - 1][hi - 1];
+    let b = h[hi - 1][hi];
+    let c = h[hi][hi - 1];
+    let d = h[hi][hi];
+
+    let half = T::from_f64( 0.5 );
+    let mean = ( a + d ) * half;
+    let offset = ( a - d ) * half;
+    let root = complex_sqrt( offset * offset + b * c );
+
+    let first = mean + root;
+    let second = mean - root;
+    if ( first - d ).modulus() <= ( second - d ).modulus() { first } else { second }
+}
+// One shifted QR step H - μI = QR, H <- RQ + μI on the block lo..=hi, with
+// the rotations also applied to the rest of H and accumulated into z so the
+// final triangle is a true Schur form
+fn qr_step<T: Float> ( h: &mut Matrix<T>, z: &mut Matrix<T>, lo: usize, hi: usize, shift: ComplexNumber<T> ) {
+    let n = h.rows;
+    for k in lo..=hi {
+        h[k][k] -= shift;
+    }
+
+    // Givens rotations G = [[c̄, s̄], [-s, c]] zeroing the subdiagonal
+    let mut rotations = Vec::with_capacity( hi - lo );
+    for k in lo..hi {
+        let x = h[k][k];
+        let y = h[k + 1][k];
+        let r = ( x.norm_sqr() + y.norm_sqr() ).sqrt();
+        let (c, s) = if r == T::zero() {
+            ( ComplexNumber::one(), ComplexNumber::zero() )
+        } else {
+            ( x / r, y / r )
+        };
+        for col in k..n {
+            let top = h[k][col];
+            let bottom = h[k + 1][col];
+            h[k][col] = c.conj() * top + s.conj() * bottom;
+            h[k + 1][col] = c * bottom - s * top;
+        }
+        rotations.push( (c, s) );
+    }
+    // Multiplying back on the right by each G†
+    for (offset, (c, s)) in rotations.into_iter().enumerate() {
+        let k = lo + offset;
+        for row in 0..=( k + 1 ) {
+            rotate_columns( h, row, k, c, s );
+        }
+        for row in 0..n {
+            rotate_columns( z, row, k, c, s );
+        }
+    }
+
+    for k in lo..=hi {
+        h[k][k] += shift;
+    }
+}
+fn rotate_columns<T: Float> ( m: &mut Matrix<T>, row: usize, k: usize, c: ComplexNumber<T>, s: ComplexNumber<T> ) {
+    let left = m[row][k];
+    let right = m[row][k + 1];
+    m[row][k] = left * c + right * s;
+    m[row][k + 1] = right * c.conj() - left * s.conj();
+}
+// Principal square root
+fn complex_sqrt<T: Float> ( z: ComplexNumber<T> ) -> ComplexNumber<T> {
+    let modulus = z.modulus();
+    let half = T::from_f64( 0.5 );
+    let re = ( ( modulus + z.a ) * half ).max( T::zero() ).sqrt();
+    let im = ( ( modulus - z.a ) * half ).max( T::zero() ).sqrt();
+
+    ComplexNumber::new( re, if z.b < T::zero() { -im } else { im } )
+}
+
+// Zeroes a[p][q] (and a[q][p]) with the unitary G = diag(1, w̄) R, where w is
+// the phase of a[p][q] and R the real Jacobi rotation of the then real 2x2
+// block. A <- G† A G and V <- V G, touching only rows and columns p and q.
+fn rotate<T: Float> ( a: &mut Matrix<T>, v: &mut Matrix<T>, p: usize, q: usize ) {
+    let apq = a[p][q];
+    let modulus = apq.modulus();
+    if modulus == T::zero() {
+        return;
+    }
+    let w = apq / modulus;
+    let w_bar = w.conj();
+
+    let two = T::from_f64( 2.0 );
+    let tau = ( a[q][q].a - a[p][p].a ) / ( two * modulus );
+    let t = if tau >= T::zero() {
+        T::one() / ( tau + ( T::one() + tau * tau ).sqrt() )
+    } else {
+        -T::one() / ( -tau + ( T::one() + tau * tau ).sqrt() )
+    };
+    let c = T::one() / ( T::one() + t * t ).sqrt();
+    let s = t * c;
+
+    let g_pp = ComplexNumber::new( c, T::zero() );
+    let g_pq = ComplexNumber::new( s, T::zero() );
+    let g_qp = w_bar * -s;
+    let g_qq = w_bar * c;
+
+    // Columns: A <- A G
+    for k in 0..a.rows {
+        let akp = a[k][p];
+        let akq = a[k][q];
+        a[k][p] = akp * g_pp + akq * g_qp;
+        a[k][q] = akp * g_pq + akq * g_qq;
+    }
+    // Rows: A <- G† A
+    for k in 0..a.cols {
+        let apk = a[p][k];
+        let aqk = a[q][k];
+        a[p][k] = g_pp.conj() * apk + g_qp.conj() * aqk;
+        a[q][k] = g_pq.conj() * apk + g_qq.conj() * aqk;
+    }
+    // The rotation zeroes these exactly; don't leave rounding behind
+    a[p][q] = ComplexNumber::zero();
+    a[q][p] = ComplexNumber::zero();
+
+    for k in 0..v.rows {
+        let vkp = v[k][p];
+        let vkq = v[k][q];
+        v[k][p] = vkp * g_pp + vkq * g_qp;
+        v[k][q] = vkp * g_pq + vkq * g_qq;
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::{
+    Matrix,
+    Gate
+};
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+impl<T: Float> Matrix<T> {
+    // Schmidt decomposition |ψ> = Σ s_k |a_k> ⊗ |b_k> of a bipartite pure
+    // state, given as a column vector whose first factor has dimension
+    // `left_dimension`. Returns (s_k, |a_k>, |b_k>) for every non-zero
+    // coefficient, largest first.
+    pub fn schmidt_decomposition ( &self, left_dimension: usize ) -> Result<Vec<(T, Self, Self)>> {
+        if self.cols != 1 {
+            return Err(QuantumError::DimensionMismatch { expected: (self.rows, 1), found: (self.rows, self.cols) });
+        }
+        if left_dimension == 0 || !self.rows.is_multiple_of( left_dimension ) {
+            return Err(QuantumError::DimensionMismatch { expected: (left_dimension, 1), found: (self.rows, 1) });
+        }
+        let right_dimension = self.rows / left_dimension;
+
+        // |ψ> as a left x right matrix M, so that ρ_A = M M†
+        let mut m = Self::from_dimensions( left_dimension, right_dimension );
+        for i in 0..left_dimension {
+            for j in 0..right_dimension {
+                m[i][j] = self[i * right_dimension + j][0];
+            }
+        }
+        let rho = &m * &m.dagger();
+        let (eigenvalues, eigenvectors) = rho.jacobi_eigen()?;
+
+        let mut ret = Vec::new();
+        for k in (0..left_dimension).rev() {
+            if eigenvalues[k] <= T::tolerance() {
+                continue;
+            }
+            let coefficient = eigenvalues[k].sqrt();
+
+            let left = Self::from_rows( (0..left_dimension).map(|i| vec![eigenvectors[i][k]]).collect() );
+            // |b_k> = (<a_k| ⊗ I) |ψ> / s_k
+            let right = Self::from_rows(
+                (0..right_dimension)
+                    .map(|j| {
+                        let amplitude = (0..left_dimension)
+                            .fold(ComplexNumber::zero(), |acc, i| acc + eigenvectors[i][k].conj() * m[i][j]);
+                        vec![amplitude / coefficient]
+                    })
+                    .collect()
+            );
+            ret.push( (coefficient, left, right) );
+        }
+
+        Ok(ret)
+    }
+
+    // S(ρ) = -Tr ρ log2 ρ of a density matrix, in bits
+    pub fn von_neumann_entropy ( &self ) -> Result<T> {
+        self.check_density()?;
+
+        let ln_2 = T::from_f64( 2.0 ).ln();
+        Ok(self.jacobi_eigen()?.0
+            .into_iter()
+            .filter(|&p| p > T::zero())
+            .map(|p| -p * p.ln() / ln_2)
+            .sum())
+    }
+    // S_α(ρ) = log2( Tr ρ^α ) / (1 - α), in bits. α = 1 is the von Neumann
+    // entropy and α = 2 the collision entropy, -log2 of the purity.
+    pub fn renyi_entropy ( &self, alpha: T ) -> Result<T> {
+        if ( alpha - T::one() ).abs() <= T::epsilon() {
+            return self.von_neumann_entropy();
+        }
+        self.check_density()?;
+
+        let trace: T = self.jacobi_eigen()?.0
+            .into_iter()
+            .filter(|&p| p > T::zero())
+            .map(|p| p.powf( alpha ))
+            .sum();
+        Ok(trace.ln() / T::from_f64( 2.0 ).ln() / ( T::one() - alpha ))
+    }
+
+    // Wootters concurrence of a two-qubit state, given as a 4x1 ket or a 4x4
+    // density matrix. 0 for separable states, 1 for Bell states.
+    pub fn concurrence ( &self ) -> Result<T> {
+        let rho = self.two_qubit_density()?;
+
+        // ρ~ = (Y ⊗ Y) ρ* (Y ⊗ Y)
+        let yy = Gate::Y().tensor_product( &Gate::Y() );
+        let rho_tilde = yy.clone() * rho.conj() * yy;
+
+        // The square roots of the eigenvalues of ρ ρ~ are those of the
+        // Hermitian √ρ ρ~ √ρ, which avoids a non-Hermitian eigensolver
+        let sqrt_rho = rho.hermitian_map(|p| p.max( T::zero() ).sqrt())?;
+        let r = sqrt_rho.clone() * rho_tilde * sqrt_rho;
+
+        let mut lambdas: Vec<T> = r.jacobi_eigen()?.0
+            .into_iter()
+            .map(|mu| mu.max( T::zero() ).sqrt())
+            .collect();
+        lambdas.sort_by(|x, y| y.partial_cmp( x ).unwrap_or( std::cmp::Ordering::Equal ));
+
+        Ok(( lambdas[0] - lambdas[1] - lambdas[2] - lambdas[3] ).max( T::zero() ))
+    }
+    // Negativity (||ρ^T_B||_1 - 1) / 2 of a two-qubit state, given as a 4x1
+    // ket or a 4x4 density matrix: the magnitude of the negative eigenvalues
+    // of the partial transpose. 0 for separable states, 1/2 for Bell states.
+    pub fn negativity ( &self ) -> Result<T> {
+        let rho = self.two_qubit_density()?;
+
+        Ok(rho.partial_transpose( &[1] )?.jacobi_eigen()?.0
+            .into_iter()
+            .filter(|&p| p < T::zero())
+            .map(|p| -p)
+            .sum())
+    }
+    // Transposes the `qubits` factors of a 2^n x 2^n operator, leaving the
+    // others alone
+    pub fn partial_transpose ( &self, qubits: &[usize] ) -> Result<Self> {
+        self.check_square()?;
+        let n = self.rows.trailing_zeros() as usize;
+        if !self.rows.is_power_of_two() {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows.next_power_of_two(), self.rows.next_power_of_two()),
+                found: (self.rows, self.cols)
+            });
+        }
+        let mut mask = 0usize;
+        for &index in qubits {
+            if index >= n {
+                return Err(QuantumError::QubitOutOfRange { index, qubits: n });
+            }
+            mask |= 1 << ( n - 1 - index );
+        }
+
+        let mut ret = Self::from_dimensions( self.rows, self.cols );
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                // Swap the transposed qubits' bits between row and column
+                let swapped = ( r ^ c ) & mask;
+                ret[r ^ swapped][c ^ swapped] = self[r][c];
+            }
+        }
+        Ok(ret)
+    }
+
+    fn two_qubit_density ( &self ) -> Result<Self> {
+        match (self.rows, self.cols) {
+            (4, 1) => {
+                self.check_ket()?;
+                self.outer_product( self )
+            },
+            (4, 4) => {
+                self.check_density()?;
+                Ok(self.clone())
+            },
+            found => Err(QuantumError::DimensionMismatch { expected: (4, 4), found })
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// Coefficients of the [6/6] Padé approximant to e^x, whose error is below
+// double precision once the 1-norm has been scaled down to 1/2
+const PADE: [f64; 7] = [
+    1.0,
+    1.0 / 2.0,
+    5.0 / 44.0,
+    1.0 / 66.0,
+    1.0 / 792.0,
+    1.0 / 15840.0,
+    1.0 / 665280.0
+];
+
+impl<T: Float> Matrix<T> {
+    // e^A by scaling and squaring: e^A = (e^{A / 2^s})^{2^s}, with the inner
+    // exponential from a Padé approximant. Works for any square matrix, so
+    // e^{-iHt} for a Hamiltonian H is (H * -it).expm().
+    pub fn expm ( &self ) -> Result<Self> {
+        self.check_square()?;
+        let n = self.rows;
+
+        let norm = (0..n)
+            .map(|c| (0..n).map(|r| self[r][c].modulus()).sum::<T>())
+            .fold(T::zero(), T::max);
+        let mut squarings = 0;
+        let mut scale = T::one();
+        while norm * scale > T::from_f64( 0.5 ) {
+            scale /= T::from_f64( 2.0 );
+            squarings += 1;
+        }
+        let a = self.clone() * ComplexNumber::new( scale, T::zero() );
+
+        // N = Σ c_k A^k and D = Σ (-1)^k c_k A^k, so e^A ≈ D^-1 N
+        let mut numerator = Self::identity( n );
+        let mut denominator = Self::identity( n );
+        let mut power = Self::identity( n );
+        for (k, &coefficient) in PADE.iter().enumerate().skip( 1 ) {
+            power *= a.clone();
+            let term = power.clone() * ComplexNumber::new( T::from_f64( coefficient ), T::zero() );
+            if k % 2 == 0 {
+                denominator += term.clone();
+            } else {
+                denominator -= term.clone();
+            }
+            numerator += term;
+        }
+
+        let mut ret = denominator.solve( &numerator )?;
+        for _ in 0..squarings {
+            ret = &ret * &ret;
+        }
+        Ok(ret)
+    }
+    // f(A) = Z f(Λ) Z† for a normal matrix A = Z Λ Z†, which includes every
+    // Hermitian and every unitary matrix. f sees each eigenvalue once.
+    pub fn func ( &self, f: impl Fn(ComplexNumber<T>) -> ComplexNumber<T> ) -> Result<Self> {
+        self.check_square()?;
+        let n = self.rows;
+
+        // A is normal exactly when its Schur form is diagonal
+        let (schur, z) = self.schur()?;
+        let scale = schur.data
+            .iter()
+            .map(|entry| entry.modulus())
+            .fold(T::one(), T::max);
+        for r in 0..n {
+            for c in r + 1..n {
+                if schur[r][c].modulus() > T::tolerance() * scale {
+                    return Err(QuantumError::NonNormalMatrix);
+                }
+            }
+        }
+
+        let mut ret = Self::from_dimensions( n, n );
+        for k in 0..n {
+            let value = f( schur[k][k] );
+            for r in 0..n {
+                for c in 0..n {
+                    let z_ck = z[c][k];
+                    ret[r][c] += z[r][k] * value * z_ck.conj();
+                }
+            }
+        }
+        Ok(ret)
+    }
+    // Principal square root of a normal matrix, so Gate::X().sqrtm() is √X
+    pub fn sqrtm ( &self ) -> Result<Self> {
+        self.powm( T::from_f64( 0.5 ) )
+    }
+    // Principal logarithm of a normal matrix, the inverse of expm()
+    pub fn logm ( &self ) -> Result<Self> {
+        self.func(|z| *principal( z ).ln())
+    }
+    // A^t of a normal matrix on the principal branch, as in the fractional
+    // gates X^t. Every eigenvalue λ becomes e^{t ln λ}, and 0 stays 0.
+    pub fn powm ( &self, t: T ) -> Result<Self> {
+        self.func(|z| {
+            if z == ComplexNumber::zero() {
+                return z;
+            }
+            let mut exponent = *principal( z ).ln() * t;
+            *exponent.exp()
+        })
+    }
+}
+
+// Eigenvalues of e.g. Z come out as -1 ± 1e-17i, straddling the branch cut;
+// put anything that close to the negative real axis on its upper side so
+// that Z^(1/4) is T rather than T†
+fn principal<T: Float> ( z: ComplexNumber<T> ) -> ComplexNumber<T> {
+    if z.a < T::zero() && z.b.abs() <= T::tolerance() * z.modulus() {
+        return ComplexNumber::new( z.a, T::zero() );
+    }
+    z
+}
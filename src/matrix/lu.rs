@@ -0,0 +1,118 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// PA = LU of a square matrix, with L unit lower triangular and U upper
+// triangular, both packed into one matrix. Row i of PA is row
+// permutation[i] of A.
+pub(crate) struct Lu<T: Float> {
+    packed: Matrix<T>,
+    permutation: Vec<usize>,
+    // An odd number of row swaps flips the sign of the determinant
+    odd: bool,
+    singular: bool
+}
+impl<T: Float> Lu<T> {
+    pub(crate) fn determinant ( &self ) -> ComplexNumber<T> {
+        let product = (0..self.packed.rows)
+            .fold(ComplexNumber::one(), |acc, i| acc * self.packed[i][i]);
+
+        if self.odd { -product } else { product }
+    }
+    // X with AX = B, for every column of B at once
+    pub(crate) fn solve ( &self, b: &Matrix<T> ) -> Result<Matrix<T>> {
+        let n = self.packed.rows;
+        if b.rows != n {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (n, b.cols),
+                found: (b.rows, b.cols)
+            });
+        }
+        if self.singular {
+            return Err(QuantumError::SingularMatrix);
+        }
+        let lu = &self.packed;
+
+        let mut x = Matrix::from_dimensions( n, b.cols );
+        for col in 0..b.cols {
+            // Ly = Pb, then Ux = y
+            let mut y: Vec<ComplexNumber<T>> = self.permutation.iter().map(|&r| b[r][col]).collect();
+            for i in 0..n {
+                let sum = (0..i).fold(ComplexNumber::zero(), |acc, k| acc + lu[i][k] * y[k]);
+                y[i] -= sum;
+            }
+            for i in (0..n).rev() {
+                let sum = (i + 1..n).fold(ComplexNumber::zero(), |acc, k| acc + lu[i][k] * y[k]);
+                y[i] = ( y[i] - sum ) / lu[i][i];
+            }
+
+            for (i, value) in y.into_iter().enumerate() {
+                x[i][col] = value;
+            }
+        }
+        Ok(x)
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    // Gaussian elimination with partial pivoting: each column's pivot is its
+    // largest remaining entry, which keeps the multipliers at most 1
+    pub(crate) fn lu ( &self ) -> Result<Lu<T>> {
+        self.check_square()?;
+        let n = self.rows;
+
+        // Pivots this small next to the matrix's largest entry are rounding
+        // noise, and dividing by them would only produce garbage
+        let scale = self.data
+            .iter()
+            .map(|entry| entry.modulus())
+            .fold(T::zero(), T::max);
+        let threshold = T::epsilon() * T::from_f64( n as f64 ) * scale;
+
+        let mut packed = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut odd = false;
+        let mut singular = scale == T::zero();
+
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|&x, &y| {
+                    packed[x][k].modulus()
+                        .partial_cmp( &packed[y][k].modulus() )
+                        .unwrap_or( std::cmp::Ordering::Equal )
+                })
+                .unwrap_or( k );
+            if pivot != k {
+                packed.swap_rows( pivot, k );
+                permutation.swap( pivot, k );
+                odd = !odd;
+            }
+
+            let diagonal = packed[k][k];
+            if diagonal.modulus() <= threshold {
+                singular = true;
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = packed[i][k] / diagonal;
+                packed[i][k] = factor;
+                for j in k + 1..n {
+                    let u_kj = packed[k][j];
+                    packed[i][j] -= factor * u_kj;
+                }
+            }
+        }
+
+        Ok(Lu { packed, permutation, odd, singular })
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// Distances between states and between gates. States are given either as an
+// n x 1 ket or an n x n density matrix, and the two sides may mix forms.
+impl<T: Float> Matrix<T> {
+    // F(ρ, σ) = (Tr √(√ρ σ √ρ))², which is |<ψ|φ>|² for two kets and
+    // <ψ|σ|ψ> when one side is a ket. 1 for identical states.
+    pub fn fidelity ( &self, other: &Self ) -> Result<T> {
+        self.check_state_pair( other )?;
+
+        match (self.cols, other.cols) {
+            (1, 1) => Ok(self.inner_product( other )?.norm_sqr()),
+            (1, _) => Ok(expectation( other, self )),
+            (_, 1) => Ok(expectation( self, other )),
+            _ => {
+                let sqrt_rho = self.hermitian_map(|p| p.max( T::zero() ).sqrt())?;
+                let root_trace: T = ( sqrt_rho.clone() * other.clone() * sqrt_rho )
+                    .jacobi_eigen()?.0
+                    .into_iter()
+                    .map(|p| p.max( T::zero() ).sqrt())
+                    .sum();
+
+                Ok(root_trace.powi( 2 ).min( T::one() ))
+            }
+        }
+    }
+    // D(ρ, σ) = ||ρ - σ||_1 / 2, the best advantage in telling ρ and σ apart
+    // with a single measurement. Between 0 and 1.
+    pub fn trace_distance ( &self, other: &Self ) -> Result<T> {
+        let difference = self.state_difference( other )?;
+
+        let norm: T = difference.jacobi_eigen()?.0
+            .into_iter()
+            .map(|p| p.abs())
+            .sum();
+        Ok(norm / T::from_f64( 2.0 ))
+    }
+    // ||ρ - σ||_2 = √Tr (ρ - σ)²
+    pub fn hilbert_schmidt_distance ( &self, other: &Self ) -> Result<T> {
+        let difference = self.state_difference( other )?;
+
+        Ok(difference.data
+            .iter()
+            .map(|entry| entry.norm_sqr())
+            .sum::<T>()
+            .sqrt())
+    }
+    // D_B(ρ, σ) = √( 2 (1 - √F(ρ, σ)) )
+    pub fn bures_distance ( &self, other: &Self ) -> Result<T> {
+        let fidelity = self.fidelity( other )?;
+
+        Ok(( T::from_f64( 2.0 ) * ( T::one() - fidelity.sqrt() ) ).max( T::zero() ).sqrt())
+    }
+
+    // Fidelity between the unitaries U and V averaged over all pure inputs,
+    // (d + |Tr U†V|²) / (d (d + 1)). Ignores global phase.
+    pub fn average_gate_fidelity ( &self, other: &Self ) -> Result<T> {
+        self.check_unitary_pair( other )?;
+
+        let d = T::from_f64( self.rows as f64 );
+        let overlap = trace( &( &self.dagger() * other ) );
+        Ok(( d + overlap.norm_sqr() ) / ( d * ( d + T::one() ) ))
+    }
+    // Diamond-norm distance ||U·U† - V·V†||_◇ between the channels of two
+    // unitaries, between 0 and 2. If the eigenvalues of U†V fit in an arc of
+    // angle θ < π it is 2 sin(θ / 2), otherwise 2. Exact for unitaries, and
+    // an upper bound on how much any experiment could tell U and V apart.
+    pub fn diamond_distance ( &self, other: &Self ) -> Result<T> {
+        self.check_unitary_pair( other )?;
+
+        let mut phases: Vec<T> = ( &self.dagger() * other )
+            .eigen()?.0
+            .into_iter()
+            .map(|z| z.b.atan2( z.a ))
+            .collect();
+        phases.sort_by(|x, y| x.partial_cmp( y ).unwrap_or( std::cmp::Ordering::Equal ));
+
+        // The smallest arc holding every phase skips the widest gap between
+        // neighbours, counting the wrap-around one
+        let mut widest_gap = phases[0] + T::tau() - phases[phases.len() - 1];
+        for pair in phases.windows( 2 ) {
+            widest_gap = widest_gap.max( pair[1] - pair[0] );
+        }
+        let arc = T::tau() - widest_gap;
+
+        if arc >= T::pi() {
+            return Ok(T::from_f64( 2.0 ));
+        }
+        Ok(T::from_f64( 2.0 ) * ( arc / T::from_f64( 2.0 ) ).sin())
+    }
+
+    // Both sides must be valid states, kets of unit norm or density matrices
+    // of unit trace, since the Hermitian eigensolver behind these metrics
+    // silently gives wrong answers for anything else
+    fn check_state_pair ( &self, other: &Self ) -> Result<()> {
+        for state in [self, other] {
+            if state.cols == 1 {
+                state.check_ket()?;
+            } else {
+                state.check_density()?;
+            }
+        }
+        if self.rows != other.rows {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, other.cols),
+                found: (other.rows, other.cols)
+            });
+        }
+        Ok(())
+    }
+    // ρ - σ, with kets turned into density matrices first
+    fn state_difference ( &self, other: &Self ) -> Result<Self> {
+        self.check_state_pair( other )?;
+
+        Ok(density( self )? - density( other )?)
+    }
+    fn check_unitary_pair ( &self, other: &Self ) -> Result<()> {
+        self.check_square()?;
+        self.check_same_dimensions( other )?;
+        if !self.unitary() || !other.unitary() {
+            return Err(QuantumError::NonUnitaryGate);
+        }
+        Ok(())
+    }
+}
+
+fn density<T: Float> ( state: &Matrix<T> ) -> Result<Matrix<T>> {
+    if state.cols == 1 {
+        return state.outer_product( state );
+    }
+    Ok(state.clone())
+}
+// <ψ|ρ|ψ>, real for a Hermitian ρ
+fn expectation<T: Float> ( rho: &Matrix<T>, ket: &Matrix<T> ) -> T {
+    let mut ret = ComplexNumber::zero();
+    for r in 0..rho.rows {
+        for c in 0..rho.cols {
+            ret += ket[r][0].conj() * rho[r][c] * ket[c][0];
+        }
+    }
+    ret.a
+}
+fn trace<T: Float> ( matrix: &Matrix<T> ) -> ComplexNumber<T> {
+    (0..matrix.rows).fold(ComplexNumber::zero(), |acc, i| acc + matrix[i][i])
+}
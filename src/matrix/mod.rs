@@ -1,17 +1,28 @@
-use crate::complex;
-
-const ONE_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0.707106781f32, b: 0f32 };
-const I_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0f32, b: 0.707106781f32 };
-const NEG_ONE_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: -0.707106781f32, b: 0f32 };
-const NEG_I_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0f32, b: -0.707106781f32 };
-
-const SQRT_TWO_OVER_TWO: f32 = std::f32::consts::SQRT_2 / 2f32;
-
+mod eigen;
+mod entanglement;
+mod functions;
+mod lu;
+mod metrics;
+mod svd;
+mod view;
+
+pub use view::MatrixView;
+
+use crate::{
+    complex,
+    error,
+    float
+};
 
 use complex::{
     ComplexNumber,
     //ComplexPolarNumber
 };
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
 
 use std::ops::{
     Add,
@@ -28,19 +39,21 @@ use std::ops::{
     IndexMut
 };
 
+// Entries are stored flat in row-major order, so entry (r, c) lives at
+// data[r * cols + c] and matrix[r][c] indexes a row slice
 #[derive(Clone, PartialEq)]
-pub struct Matrix {
-    pub value: Vec<Vec<ComplexNumber>>,
+pub struct Matrix<T: Float = f64> {
+    pub data: Vec<ComplexNumber<T>>,
     pub rows: usize,
     pub cols: usize
 }
-impl std::fmt::Debug for Matrix {
+impl<T: Float> std::fmt::Debug for Matrix<T> {
     fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
-        write!(f, "Complex Matrix: {}x{}\n", self.rows, self.cols )?;
+        writeln!(f, "Complex Matrix: {}x{}", self.rows, self.cols )?;
         let mut row_divider: String = String::new();
-        for row in &self.value {
+        for row in self.data.chunks( self.cols.max(1) ) {
             let stringified_row = row
-                .into_iter()
+                .iter()
                 .map(|i| format!("{:?}",i))
                 .collect::<Vec<String>>()
                 .join(" | ");
@@ -51,327 +64,205 @@ impl std::fmt::Debug for Matrix {
         Ok(())
     }
 }
-impl Add<ComplexNumber> for Matrix {
+impl<T: Float> Add<ComplexNumber<T>> for Matrix<T> {
     type Output = Self;
 
-    fn add ( self, to_add: ComplexNumber ) -> Self {
-        let mut ret = self.clone();
-
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] += to_add.clone();
-            }
-        }
-
-        ret
+    fn add ( mut self, to_add: ComplexNumber<T> ) -> Self {
+        self += to_add;
+        self
     }
 }
-impl Add<Matrix> for Matrix {
+impl<T: Float> Add<Matrix<T>> for Matrix<T> {
     type Output = Self;
 
-    fn add ( self, to_add: Self ) -> Self {
-        if self.rows != to_add.rows || self.cols != to_add.cols {
-            panic!("Matrix size {}x{} doesn't match the base size {}x{}", to_add.rows, to_add.cols, self.rows, self.cols);
-        }
-
-        let mut ret = self.clone();
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] += to_add.value[row][col].clone();
-            }
-        }
-
-        ret
+    fn add ( mut self, to_add: Self ) -> Self {
+        self += to_add;
+        self
     }
 }
-impl AddAssign<ComplexNumber> for Matrix {
-    fn add_assign ( &mut self, to_add: ComplexNumber ) {
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] += to_add.clone();
-            }
+impl<T: Float> AddAssign<ComplexNumber<T>> for Matrix<T> {
+    fn add_assign ( &mut self, to_add: ComplexNumber<T> ) {
+        for entry in self.data.iter_mut() {
+            *entry += to_add;
         }
     }
 }
-impl AddAssign<Matrix> for Matrix {
+impl<T: Float> AddAssign<Matrix<T>> for Matrix<T> {
     fn add_assign ( &mut self, to_add: Self ) {
         if self.rows != to_add.rows || self.cols != to_add.cols {
             panic!("Matrix size {}x{} doesn't match the base size {}x{}", to_add.rows, to_add.cols, self.rows, self.cols);
         }
 
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] += to_add.value[row][col].clone();
-            }
+        for (entry, added) in self.data.iter_mut().zip( to_add.data ) {
+            *entry += added;
         }
     }
 }
-impl Sub<ComplexNumber> for Matrix {
+impl<T: Float> Sub<ComplexNumber<T>> for Matrix<T> {
     type Output = Self;
 
-    fn sub ( self, to_add: ComplexNumber ) -> Self {
-        let mut ret = self.clone();
-
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] -= to_add.clone();
-            }
-        }
-
-        ret
+    fn sub ( mut self, to_sub: ComplexNumber<T> ) -> Self {
+        self -= to_sub;
+        self
     }
 }
-impl Sub<Matrix> for Matrix {
+impl<T: Float> Sub<Matrix<T>> for Matrix<T> {
     type Output = Self;
 
-    fn sub ( self, to_add: Self ) -> Self {
-        if self.rows != to_add.rows || self.cols != to_add.cols {
-            panic!("Matrix size {}x{} doesn't match the base size {}x{}", to_add.rows, to_add.cols, self.rows, self.cols);
-        }
-
-        let mut ret = self.clone();
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] -= to_add.value[row][col].clone();
-            }
-        }
-
-        ret
+    fn sub ( mut self, to_sub: Self ) -> Self {
+        self -= to_sub;
+        self
     }
 }
-impl SubAssign<ComplexNumber> for Matrix {
-    fn sub_assign ( &mut self, to_add: ComplexNumber ) {
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] -= to_add.clone();
-            }
+impl<T: Float> SubAssign<ComplexNumber<T>> for Matrix<T> {
+    fn sub_assign ( &mut self, to_sub: ComplexNumber<T> ) {
+        for entry in self.data.iter_mut() {
+            *entry -= to_sub;
         }
     }
 }
-impl SubAssign<Matrix> for Matrix {
-    fn sub_assign ( &mut self, to_add: Self ) {
-        if self.rows != to_add.rows || self.cols != to_add.cols {
-            panic!("Matrix size {}x{} doesn't match the base size {}x{}", to_add.rows, to_add.cols, self.rows, self.cols);
+impl<T: Float> SubAssign<Matrix<T>> for Matrix<T> {
+    fn sub_assign ( &mut self, to_sub: Self ) {
+        if self.rows != to_sub.rows || self.cols != to_sub.cols {
+            panic!("Matrix size {}x{} doesn't match the base size {}x{}", to_sub.rows, to_sub.cols, self.rows, self.cols);
         }
 
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] -= to_add.value[row][col].clone();
-            }
+        for (entry, subtracted) in self.data.iter_mut().zip( to_sub.data ) {
+            *entry -= subtracted;
         }
     }
 }
-impl Mul<ComplexNumber> for Matrix {
+impl<T: Float> Mul<ComplexNumber<T>> for Matrix<T> {
     type Output = Self;
 
-    fn mul ( self, to_mul: ComplexNumber ) -> Self {
-        let mut ret = self.clone();
-
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] *= to_mul.clone();
-            }
-        }
-
-        ret
+    fn mul ( mut self, to_mul: ComplexNumber<T> ) -> Self {
+        self *= to_mul;
+        self
     }
 }
-impl Mul<Matrix> for Matrix {
+impl<T: Float> Mul<Matrix<T>> for Matrix<T> {
     type Output = Self;
 
     fn mul ( self, to_mul: Self ) -> Self {
+        &self * &to_mul
+    }
+}
+// Multiplies without consuming or cloning either side
+impl<T: Float> Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul ( self, to_mul: &Matrix<T> ) -> Matrix<T> {
         if self.cols != to_mul.rows {
-            panic!("Number of columns in the base matrix must match the number of columns in the second row!");
+            panic!("Number of columns in the base matrix must match the number of rows in the second matrix!");
         }
 
         let mut end_result = Matrix::from_dimensions( self.rows, to_mul.cols );
-
-        for r in 0..end_result.value.len() {
-            for c in 0..(end_result.value[r].len()) {
-                let row: Vec<ComplexNumber> = self.value[r].clone();
-                let col: Vec<ComplexNumber> = to_mul
-                    .value
-                    .clone()
-                    .into_iter()
-                    .map(|i| i[c].clone())
-                    .collect();
-                
-                let dot_product: ComplexNumber = row
-                    .clone()
-                    .into_iter()
-                    .enumerate()
-                    .map(|(ind, i)| { // Multiply each row by element of equivalent index in each intersecting column
-                        i.clone() * col[ind].clone()
-                    })
-                    .reduce(|total: ComplexNumber, i: ComplexNumber| { // Add each element of the now row to create the final dot product
-                        total + i
-                    })
-                    .expect("Should never happen given the matrices make it past first check")
-                    .clone();
-
-                end_result.value[r][c] = dot_product;
-            }
-        }
-
+        view::gemm( self.view(), to_mul.view(), &mut end_result );
         end_result
     }
 }
-impl MulAssign<ComplexNumber> for Matrix {
-    fn mul_assign ( &mut self, to_mul: ComplexNumber ) {
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] *= to_mul.clone();
-            }
+impl<T: Float> MulAssign<ComplexNumber<T>> for Matrix<T> {
+    fn mul_assign ( &mut self, to_mul: ComplexNumber<T> ) {
+        for entry in self.data.iter_mut() {
+            *entry *= to_mul;
         }
     }
 }
-impl MulAssign<Matrix> for Matrix {
+impl<T: Float> MulAssign<Matrix<T>> for Matrix<T> {
     fn mul_assign ( &mut self, to_mul: Self ) {
-        if self.cols != to_mul.rows {
-            panic!("Number of columns in the base matrix must match the number of columns in the second row!");
-        }
-
-        let mut end_result = Matrix::from_dimensions( self.rows, to_mul.cols );
-
-        for r in 0..end_result.value.len() {
-            for c in 0..(end_result.value[r].len()) {
-                let row: Vec<ComplexNumber> = self.value[r].clone();
-                let col: Vec<ComplexNumber> = to_mul
-                    .value
-                    .clone()
-                    .into_iter()
-                    .map(|i| i[c].clone())
-                    .collect();
-                
-                let dot_product: ComplexNumber = row
-                    .clone()
-                    .into_iter()
-                    .enumerate()
-                    .map(|(ind, i)| { // Multiply each row by element of equivalent index in each intersecting column
-                        i.clone() * col[ind].clone()
-                    })
-                    .reduce(|total: ComplexNumber, i: ComplexNumber| { // Add each element of the now row to create the final dot product
-                        total + i
-                    })
-                    .expect("Should never happen given the matrices make it past first check")
-                    .clone();
-
-                end_result.value[r][c] = dot_product;
-            }
-        }
-
-        *self = end_result;
+        *self = &*self * &to_mul;
     }
 }
-impl Div<ComplexNumber> for Matrix {
+impl<T: Float> Div<ComplexNumber<T>> for Matrix<T> {
     type Output = Self;
 
-    fn div ( self, to_mul: ComplexNumber ) -> Self {
-        let mut ret = self.clone();
-
-        for row in 0..ret.value.len() {
-            for col in 0..ret.value[row].len() {
-                ret.value[row][col] /= to_mul.clone();
-            }
-        }
-
-        ret
+    fn div ( mut self, to_div: ComplexNumber<T> ) -> Self {
+        self /= to_div;
+        self
     }
 }
-impl DivAssign<ComplexNumber> for Matrix {
-    fn div_assign ( &mut self, to_mul: ComplexNumber ) {
-        for row in 0..self.value.len() {
-            for col in 0..self.value[row].len() {
-                self.value[row][col] /= to_mul.clone();
-            }
+impl<T: Float> DivAssign<ComplexNumber<T>> for Matrix<T> {
+    fn div_assign ( &mut self, to_div: ComplexNumber<T> ) {
+        for entry in self.data.iter_mut() {
+            *entry /= to_div;
         }
     }
 }
-impl Index<usize> for Matrix {
-    type Output = Vec<ComplexNumber>;
+impl<T: Float> Index<usize> for Matrix<T> {
+    type Output = [ComplexNumber<T>];
 
     fn index ( &self, index: usize ) -> &Self::Output {
-        &(self.value[index])
+        &self.data[index * self.cols..( index + 1 ) * self.cols]
     }
 }
-impl IndexMut<usize> for Matrix {
+impl<T: Float> IndexMut<usize> for Matrix<T> {
     fn index_mut ( &mut self, index: usize ) -> &mut Self::Output {
-        &mut (self.value[index])
+        &mut self.data[index * self.cols..( index + 1 ) * self.cols]
     }
 }
 
-impl Matrix {
+impl<T: Float> Matrix<T> {
     #[allow(non_snake_case)]
     pub fn IDENTITY () -> Self {
-        Matrix::new(vec![
-            vec![ ComplexNumber { a: 1f32, b: 0f32}, ComplexNumber { a: 0f32, b: 0f32} ],
-            vec![ ComplexNumber { a: 0f32, b: 0f32}, ComplexNumber { a: 1f32, b: 0f32} ]])
+        Matrix::from_rows(vec![
+            vec![ ComplexNumber::one(), ComplexNumber::zero() ],
+            vec![ ComplexNumber::zero(), ComplexNumber::one() ]])
     }
 
     // Basic Functions
-    pub fn new (value: Vec<Vec<ComplexNumber>>) -> Self {
-        if !value.iter().all(|i| i.len() == value[0].len()) {
-            panic!("Matrix is not two-dimensional! Ensure all rows are equal in length.");
+    pub fn new (value: Vec<Vec<ComplexNumber<T>>>) -> Result<Self> {
+        if value.is_empty() || value[0].is_empty() || !value.iter().all(|i| i.len() == value[0].len()) {
+            return Err(QuantumError::MalformedMatrix);
         }
+
+        Ok(Matrix::from_rows(value))
+    }
+    // For rows already known to be rectangular, such as gate literals
+    pub(crate) fn from_rows (value: Vec<Vec<ComplexNumber<T>>>) -> Self {
         let rows = value.len();
         let cols = value[0].len();
         Self {
-            value,
+            data: value.into_iter().flatten().collect(),
             rows,
             cols
         }
     }
-    pub fn from_dimensions ( rows: usize, cols: usize ) -> Self {
-        let row: Vec<ComplexNumber> = vec![ComplexNumber { a: 0f32, b: 0f32 }; cols];
-        let mut ret: Vec<Vec<ComplexNumber>> = Vec::new();
-        for _ in 0..rows {
-            ret.push( row.clone() );
+    pub fn identity ( size: usize ) -> Self {
+        let mut ret = Matrix::from_dimensions( size, size );
+        for i in 0..size {
+            ret[i][i] = ComplexNumber::one();
         }
-        Matrix::new(ret)
+        ret
     }
-    pub fn determinant ( &self ) -> ComplexNumber {
-        if self.cols != 2 || self.rows != 2 {
-            todo!();
+    pub fn from_dimensions ( rows: usize, cols: usize ) -> Self {
+        Matrix {
+            data: vec![ComplexNumber::zero(); rows * cols],
+            rows,
+            cols
         }
-        let a = self.value[0][0].clone();
-        let b = self.value[0][1].clone();
-        let c = self.value[1][0].clone();
-        let d = self.value[1][1].clone();
-
-        (a * d) - (b * c)
     }
-    pub fn invert ( &mut self ) -> &Self {
-        let determinant = self.determinant();
-
-        let a = self.value[0][0].clone();
-        let b = self.value[0][1].clone();
-        let c = self.value[1][0].clone();
-        let d = self.value[1][1].clone();
-        
-        self.value[0][0] = d / determinant.clone();
-        self.value[0][1] = b * -1f32 / determinant.clone();
-        self.value[1][0] = c * -1f32 / determinant.clone();
-        self.value[1][1] = a / determinant;
+    // Via LU decomposition, so any square size works
+    pub fn determinant ( &self ) -> Result<ComplexNumber<T>> {
+        Ok(self.lu()?.determinant())
+    }
+    // Fails with SingularMatrix rather than filling in infinities
+    pub fn invert ( &mut self ) -> Result<&Self> {
+        *self = self.lu()?.solve( &Self::identity( self.rows ) )?;
 
-        self
+        Ok(self)
+    }
+    // x with Ax = b, where b may hold several right-hand sides as columns.
+    // Cheaper and more accurate than multiplying by the inverse.
+    pub fn solve ( &self, b: &Self ) -> Result<Self> {
+        self.lu()?.solve( b )
     }
     pub fn transpose ( &mut self ) -> &Self {
-        let mut ret = Matrix::from_dimensions( self.cols, self.rows );
-
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                ret.value[c][r] = self.value[r][c].clone();
-            }
-        }
-
-        *self = ret;
+        *self = self.view().transpose().to_matrix();
         self
     }
     pub fn conjugate ( &mut self ) -> &Self {
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                self.value[r][c].conjugate();
-            }
+        for entry in self.data.iter_mut() {
+            entry.conjugate();
         }
         self
     }
@@ -379,118 +270,264 @@ impl Matrix {
         self.transpose();
         self.conjugate()
     }
-    pub fn unitary ( &self ) -> bool {
-        (self.clone() * self.clone().adjunct().clone()) == Matrix::IDENTITY()
+    // The entrywise conjugate as a new matrix, leaving self as it is
+    pub fn conj ( &self ) -> Self {
+        Self {
+            data: self.data.iter().map(|entry| entry.conj()).collect(),
+            rows: self.rows,
+            cols: self.cols
+        }
     }
-
-    // Advanced Functions
-    pub fn inner_product ( &self, to_mul: &Self ) -> ComplexNumber {
-        if self.cols != 1 || to_mul.cols != 1 {
-            panic!("Both matrices must have only one column!");
+    // The adjoint as a new matrix, leaving self as it is
+    pub fn dagger ( &self ) -> Self {
+        let mut ret = self.view().transpose().to_matrix();
+        ret.conjugate();
+        ret
+    }
+    // U U† = I, to within the default tolerance of the precision
+    pub fn unitary ( &self ) -> bool {
+        if self.rows != self.cols {
+            return false;
         }
 
-        (self.clone().adjunct().clone() * to_mul.clone()).value[0][0].clone()
+        (self * self.clone().adjunct()).approx_eq( &Matrix::identity( self.rows ), T::tolerance() )
+    }
+    // Same dimensions, and every entry within an absolute distance of eps
+    pub fn approx_eq ( &self, other: &Self, eps: T ) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data
+                .iter()
+                .zip( &other.data )
+                .all(|(x, y)| x.approx_eq( y, eps ))
+    }
+    // Same dimensions, and every entry equal to within eps relative to its size
+    pub fn relative_eq ( &self, other: &Self, eps: T ) -> bool {
+        self.rows == other.rows && self.cols == other.cols &&
+            self.data
+                .iter()
+                .zip( &other.data )
+                .all(|(x, y)| x.relative_eq( y, eps ))
     }
-    pub fn outer_product ( &self, to_mul: &Self ) -> Self {
-        if self.cols != 1 || to_mul.cols != 1 {
-            panic!("Both matrices must have only one column!");
+    // Equal up to a global phase e^(iφ), which no measurement can observe.
+    // The phase is read off the largest entry of self, then every entry
+    // is compared to within eps.
+    pub fn states_equivalent ( &self, other: &Self, eps: T ) -> bool {
+        if self.rows != other.rows || self.cols != other.cols {
+            return false;
         }
 
-        self.clone() * to_mul.clone().adjunct().clone()
-    }
-    pub fn normalize ( &mut self ) -> &Self {
-        if self.cols != 1 {
-            todo!(); // impl according to https://mathforums.com/t/how-do-i-normalize-a-matrix.18218/
+        let (pivot_row, pivot_col) = (0..self.rows)
+            .flat_map(|r| (0..self.cols).map(move |c| (r, c)))
+            .max_by(|&(r1, c1), &(r2, c2)| self[r1][c1].modulus().partial_cmp( &self[r2][c2].modulus() ).unwrap_or( std::cmp::Ordering::Equal ))
+            .expect("Matrix has at least one entry");
+        let pivot = self[pivot_row][pivot_col];
+        if pivot.approx_eq( &ComplexNumber::zero(), eps ) {
+            return self.approx_eq( other, eps );
         }
-        let norm = self.inner_product( &self ).a.sqrt();
 
-        for r in 0..self.value.len() {
-            self.value[r][0] /= norm;
+        let ratio = other[pivot_row][pivot_col] / pivot;
+        if ratio.approx_eq( &ComplexNumber::zero(), eps ) {
+            return false;
         }
+        let phase = ratio / ratio.modulus();
 
-        self
+        (self.clone() * phase).approx_eq( other, eps )
     }
 
-    // Further Advanced Functions
-    pub fn tensor_product ( &self, to_mul: &Self ) -> Self {
-        let mut ret = Matrix::from_dimensions( self.rows * to_mul.rows, self.cols * to_mul.cols );
-
-        for r in 0..self.value.len() {
-            for c in 0..self.value[r].len() {
-                for r_n in 0..to_mul.value.len() {
-                    for c_n in 0..to_mul.value[r_n].len() {
-                        ret.value[r * to_mul.value.len() + r_n][c * to_mul.value[c].len() + c_n] = self.value[r][c].clone() * to_mul.value[r_n][c_n].clone();
-                    }
-                }
-            }
+    fn check_square ( &self ) -> Result<()> {
+        if self.rows != self.cols {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, self.rows),
+                found: (self.rows, self.cols)
+            });
         }
-
-        ret
+        Ok(())
+    }
+    // A density matrix is square, Hermitian and of unit trace. Positivity
+    // isn't checked, since rounding leaves valid states with eigenvalues a
+    // hair below zero anyway.
+    fn check_density ( &self ) -> Result<()> {
+        self.check_square()?;
+        if !self.relative_eq( &self.dagger(), T::tolerance() ) {
+            return Err(QuantumError::NonHermitianMatrix);
+        }
+        let trace: T = (0..self.rows).map(|i| self[i][i].a).sum();
+        check_unit( trace, self.rows )
+    }
+    // A ket is a column of unit norm
+    fn check_ket ( &self ) -> Result<()> {
+        let norm: T = self.data.iter().map(|entry| entry.norm_sqr()).sum();
+        check_unit( norm, self.rows )
+    }
+    fn check_same_dimensions ( &self, other: &Self ) -> Result<()> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, self.cols),
+                found: (other.rows, other.cols)
+            });
+        }
+        Ok(())
+    }
+    // Non-panicking versions of the arithmetic operators
+    pub fn checked_add ( &self, to_add: &Self ) -> Result<Self> {
+        self.check_same_dimensions( to_add )?;
+        Ok(self.clone() + to_add.clone())
     }
-    pub fn eigenvalue_from_eigenvector ( &self, eigenvector: &Self ) -> Option<ComplexNumber> {
-        if self.rows != self.cols || self.cols != eigenvector.rows {
-            panic!("Arguments must be of size n x n and n x 1!");
+    pub fn checked_sub ( &self, to_sub: &Self ) -> Result<Self> {
+        self.check_same_dimensions( to_sub )?;
+        Ok(self.clone() - to_sub.clone())
+    }
+    pub fn checked_mul ( &self, to_mul: &Self ) -> Result<Self> {
+        if self.cols != to_mul.rows {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.cols, to_mul.cols),
+                found: (to_mul.rows, to_mul.cols)
+            });
         }
-        let result = self.clone() * eigenvector.clone();
+        Ok(self * to_mul)
+    }
 
-        let mut ret: Option<ComplexNumber> = None;
-        for r in 0..eigenvector.value.len() {
-            if eigenvector.value[r][0] != (ComplexNumber { a: 0f32, b: 0f32 }) {
-                let eigenvalue = result.value[0][0].clone() / eigenvector.value[0][0].clone();
-                match ret {
-                    Some(_) => return None,
-                    None => ret = Some(eigenvalue)
-                }
+    // Advanced Functions
+    fn check_column_vectors ( &self, other: &Self ) -> Result<()> {
+        for vector in [self, other] {
+            if vector.cols != 1 {
+                return Err(QuantumError::DimensionMismatch {
+                    expected: (vector.rows, 1),
+                    found: (vector.rows, vector.cols)
+                });
             }
         }
-        ret
+        Ok(())
     }
-    pub fn eigenvector_from_eigenvalue ( &self, eigenvalue: ComplexNumber ) -> Self {
-        let mut ret = Matrix::from_dimensions( 2, 1 );
+    pub fn inner_product ( &self, to_mul: &Self ) -> Result<ComplexNumber<T>> {
+        self.check_column_vectors( to_mul )?;
 
-        let a = self.value[0][0].clone();
-        let b = self.value[0][1].clone();
-        let c = self.value[1][0].clone();
-        let d = self.value[1][1].clone();
+        Ok(self.clone().adjunct().checked_mul( to_mul )?[0][0])
+    }
+    pub fn outer_product ( &self, to_mul: &Self ) -> Result<Self> {
+        self.check_column_vectors( to_mul )?;
 
-        // The many, many edge cases
-        if c == (ComplexNumber { a: 0f32, b: 0f32 }) {
-            if a - eigenvalue.clone() != (ComplexNumber { a: 0f32, b: 0f32 }) {
-                ret.value[0][0] = ComplexNumber { a: 0f32, b: 0f32 };
-                ret.value[1][0] = ComplexNumber { a: 1f32, b: 0f32 };
+        Ok(self * to_mul.clone().adjunct())
+    }
+    // Scales to unit Frobenius norm, which is the usual norm for column vectors
+    pub fn normalize ( &mut self ) -> Result<&Self> {
+        let norm = self.data
+            .iter()
+            .map(|i| i.modulus().powi(2))
+            .sum::<T>()
+            .sqrt();
+        if norm == T::zero() {
+            return Err(QuantumError::DivisionByZero);
+        }
+
+        for entry in self.data.iter_mut() {
+            *entry /= norm;
+        }
 
-                return ret;
-            } else {
-                if c == (ComplexNumber { a: 0f32, b: 0f32 }) {
-                    ret.value[0][0] = ComplexNumber { a: 1f32, b: 0f32 };
-                    ret.value[1][0] = ComplexNumber { a: 0f32, b: 0f32 };
+        Ok(self)
+    }
 
-                    return ret;
-                } else {
-                    ret.value[0][0] = (d - eigenvalue) / (c * -1f32 );
-                    ret.value[1][0] = ComplexNumber { a: 1f32, b: 0f32 };
+    // Further Advanced Functions
+    pub fn tensor_product ( &self, to_mul: &Self ) -> Self {
+        let rows = self.rows * to_mul.rows;
+        let cols = self.cols * to_mul.cols;
 
-                    return ret;
+        // Written out in storage order: each output row is one row of self
+        // with every entry scaled across one row of to_mul
+        let mut data = Vec::with_capacity( rows * cols );
+        for r in 0..self.rows {
+            for r_n in 0..to_mul.rows {
+                for &scalar in &self[r] {
+                    data.extend( to_mul[r_n].iter().map(|&entry| scalar * entry) );
                 }
             }
         }
 
-        ret.value[0][0] = ComplexNumber { a: 1f32, b: 0f32 };
-        ret.value[1][0] = (eigenvalue - a) / b;
+        Matrix { data, rows, cols }
+    }
+    pub fn eigenvalue_from_eigenvector ( &self, eigenvector: &Self ) -> Result<Option<ComplexNumber<T>>> {
+        self.check_square()?;
+        if eigenvector.rows != self.cols || eigenvector.cols != 1 {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.cols, 1),
+                found: (eigenvector.rows, eigenvector.cols)
+            });
+        }
+        let result = self * eigenvector;
+
+        // Divide through by the largest component for the best conditioned
+        // estimate, then check that A v = λ v holds for every component
+        let pivot = (0..eigenvector.rows)
+            .max_by(|&x, &y| eigenvector[x][0].modulus().partial_cmp( &eigenvector[y][0].modulus() ).unwrap_or( std::cmp::Ordering::Equal ))
+            .expect("Eigenvector has at least one row");
+        if eigenvector[pivot][0].approx_eq( &ComplexNumber::zero(), T::tolerance() ) {
+            return Ok(None);
+        }
+        let eigenvalue = result[pivot][0] / eigenvector[pivot][0];
+
+        let expected = eigenvector.clone() * eigenvalue;
+        if !result.relative_eq( &expected, T::tolerance() ) {
+            return Ok(None);
+        }
+        Ok(Some(eigenvalue))
+    }
+    // Unit eigenvector for an eigenvalue of any square matrix. Fails with
+    // NotAnEigenvalue if no eigenvalue is within tolerance of the given one.
+    pub fn eigenvector_from_eigenvalue ( &self, eigenvalue: ComplexNumber<T> ) -> Result<Self> {
+        let (eigenvalues, eigenvectors) = self.eigen()?;
+
+        let nearest = (0..eigenvalues.len())
+            .min_by(|&x, &y| {
+                ( eigenvalues[x] - eigenvalue ).modulus()
+                    .partial_cmp( &( eigenvalues[y] - eigenvalue ).modulus() )
+                    .unwrap_or( std::cmp::Ordering::Equal )
+            })
+            .ok_or( QuantumError::MalformedMatrix )?;
+        if !eigenvalues[nearest].relative_eq( &eigenvalue, T::tolerance() ) {
+            return Err(QuantumError::NotAnEigenvalue);
+        }
+
+        Ok(Self::from_rows( (0..self.rows).map(|r| vec![eigenvectors[r][nearest]]).collect() ))
+    }
+    // Traces out every qubit of a 2^n x 2^n density matrix except `keep`,
+    // giving the reduced density matrix with keep[0] as its most significant
+    // qubit. Unlike inverse_tensor_product this works for entangled states.
+    pub fn partial_trace ( &self, keep: &[usize] ) -> Result<Self> {
+        self.check_square()?;
+        if !self.rows.is_power_of_two() {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows.next_power_of_two(), self.rows.next_power_of_two()),
+                found: (self.rows, self.cols)
+            });
+        }
+        let qubits = self.rows.trailing_zeros() as usize;
+        for (i, &index) in keep.iter().enumerate() {
+            if index >= qubits {
+                return Err(QuantumError::QubitOutOfRange { index, qubits });
+            }
+            if keep[..i].contains( &index ) {
+                return Err(QuantumError::DuplicateQubit( index ));
+            }
+        }
 
-        ret
+        Ok(trace_out( qubits, keep, |r, c| self[r][c] ))
     }
-    pub fn inverse_tensor_product ( &self, old_base: Self ) -> Self {
+    pub fn inverse_tensor_product ( &self, old_base: Self ) -> Result<Self> {
+        if !self.rows.is_multiple_of( old_base.rows ) || !self.cols.is_multiple_of( old_base.cols ) {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, self.cols),
+                found: (old_base.rows, old_base.cols)
+            });
+        }
         let mult_rows = self.rows / old_base.rows;
         let mult_cols = self.cols / old_base.cols;
         let mut multiplicand = Self::from_dimensions( mult_rows, mult_cols );
         
-        if old_base.value.iter().fold( ComplexNumber{ a: 0f32, b: 0f32}, |acc, x| {
-            acc + x.iter().fold( ComplexNumber{ a: 0f32, b: 0f32 }, |acc, x| acc + x.clone() )
-        })  == (ComplexNumber{ a: 0f32, b: 0f32}) {
-            panic!("Base MUST have at least one non-zero value to produce a multiplicative array!");
+        if old_base.data.iter().all(|x| x.approx_eq( &ComplexNumber::zero(), T::tolerance() )) {
+            // Base MUST have at least one non-zero value to produce a multiplicative array
+            return Err(QuantumError::DivisionByZero);
         }
-        for row_idx in 0..self.value.len() {
+        for row_idx in 0..self.rows {
             for col_idx in 0..self[row_idx].len() {
                 let result_row_idx = if row_idx > mult_rows - 1 { (row_idx) % mult_rows } else { row_idx };
                 let result_col_idx = if col_idx > mult_cols - 1 { (col_idx) % mult_cols } else { col_idx };
@@ -498,237 +535,146 @@ impl Matrix {
                 let base_row_idx = row_idx / mult_rows;
                 let base_col_idx = col_idx / mult_cols;
  
-                if old_base[ base_row_idx][ base_col_idx ] == (ComplexNumber { a: 0f32, b: 0f32 }) {
-                    if old_base[ base_row_idx ][ base_col_idx ] != (ComplexNumber { a: 0f32, b: 0f32 }) {
-                        multiplicand[ result_row_idx ][ result_col_idx ] = ComplexNumber { a: 0f32, b: 0f32 };
-                        continue;
-                    }
+                // A (near-)zero base entry says nothing about the multiplicand
+                if old_base[ base_row_idx ][ base_col_idx ].approx_eq( &ComplexNumber::zero(), T::tolerance() ) {
                     continue;
                 }
                 
-                multiplicand[ result_row_idx ][ result_col_idx ] = self[row_idx][col_idx].clone() / old_base[ base_row_idx ][ base_col_idx ].clone();
+                multiplicand[ result_row_idx ][ result_col_idx ] = self[row_idx][col_idx] / old_base[ base_row_idx ][ base_col_idx ];
             }
         }
         
-        multiplicand
+        Ok(multiplicand)
     }
 }
 
+// A trace or squared norm of 1, allowing for rounding that grows with the
+// dimension
+fn check_unit<T: Float> ( value: T, dimension: usize ) -> Result<()> {
+    if ( value - T::one() ).abs() > T::tolerance() * T::from_f64( dimension as f64 ) {
+        return Err(QuantumError::UnnormalizedState( value.to_f64() ));
+    }
+    Ok(())
+}
 
+// Reduced density matrix of the `keep` qubits of an n-qubit state whose
+// density matrix entries are given by `entry`, for already validated indices
+pub(crate) fn trace_out<T: Float> ( qubits: usize, keep: &[usize], entry: impl Fn(usize, usize) -> ComplexNumber<T> ) -> Matrix<T> {
+    let bits: Vec<usize> = keep.iter().map(|&index| 1 << ( qubits - 1 - index )).collect();
+    let kept_mask = bits.iter().fold(0usize, |acc, bit| acc | bit);
+
+    // Spreads a k-bit sub-basis index out onto the kept qubits
+    let scatter = |sub: usize| {
+        bits
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| sub & ( 1 << ( keep.len() - 1 - j ) ) != 0)
+            .fold(0usize, |acc, (_, bit)| acc | bit)
+    };
+    let positions: Vec<usize> = (0..1usize << keep.len()).map(scatter).collect();
+
+    let mut ret = Matrix::from_dimensions( positions.len(), positions.len() );
+    for rest in (0..1usize << qubits).filter(|i| i & kept_mask == 0) {
+        for (a, &row) in positions.iter().enumerate() {
+            for (b, &col) in positions.iter().enumerate() {
+                ret[a][b] += entry( rest | row, rest | col );
+            }
+        }
+    }
+    ret
+}
 
-pub struct Gate {}
-impl Gate {
+pub struct Gate<T: Float = f64> {
+    _precision: std::marker::PhantomData<T>
+}
+#[allow(non_snake_case)]
+impl<T: Float> Gate<T> {
     /* Gate Constants */
-    pub fn I () -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }],
-        ])
+    pub fn I () -> Matrix<T> {
+        Matrix::identity( 2 )
     }
 
-    pub fn X () -> Matrix { 
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }],
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
+    pub fn X () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::zero(), ComplexNumber::one()],
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
         ])
     }
-    pub fn Y () -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: -1f32 }],
-            vec![ComplexNumber { a: 0f32, b: 1f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
+    pub fn Y () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::zero(), -ComplexNumber::i()],
+            vec![ComplexNumber::i(), ComplexNumber::zero()],
         ])
     }
-    pub fn Z () -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: -1f32, b: 0f32 }],
+    pub fn Z () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), -ComplexNumber::one()],
         ])
     }
 
-    pub fn H () -> Matrix {
-        Matrix::new(vec![
-            vec![ONE_OVER_SQRT_TWO, ONE_OVER_SQRT_TWO],
-            vec![ONE_OVER_SQRT_TWO, NEG_ONE_OVER_SQRT_TWO]
+    pub fn H () -> Matrix<T> {
+        let one_over_sqrt_two = ComplexNumber::new( T::frac_1_sqrt_2(), T::zero() );
+
+        Matrix::from_rows(vec![
+            vec![one_over_sqrt_two, one_over_sqrt_two],
+            vec![one_over_sqrt_two, -one_over_sqrt_two]
+        ])
+    }
+    pub fn S () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), ComplexNumber::i()]
         ])
     }
-    pub fn S () -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 1f32 }]
+    pub fn T () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), ComplexNumber::new( T::frac_1_sqrt_2(), T::frac_1_sqrt_2() )]
         ])
     }
-    pub fn T () -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: SQRT_TWO_OVER_TWO, b: SQRT_TWO_OVER_TWO }]
+    pub fn S_adj () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), -ComplexNumber::i()]
         ])
     }
+    pub fn T_adj () -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), ComplexNumber::new( T::frac_1_sqrt_2(), -T::frac_1_sqrt_2() )]
+        ])
+    }
+
+    pub fn R_x ( theta: T ) -> Matrix<T> {
+        let half = theta / T::from_f64( 2.0 );
 
-    pub fn R_x ( theta: f32 ) -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: (theta / 2f32).cos(), b: 0f32 }, ComplexNumber { a: 0f32, b: -(theta / 2f32).sin() }],
-            vec![ComplexNumber { a: 0f32, b: -(theta / 2f32).sin() }, ComplexNumber { a: (theta / 2f32).cos(), b: 0f32 }]
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::new( half.cos(), T::zero() ), ComplexNumber::new( T::zero(), -half.sin() )],
+            vec![ComplexNumber::new( T::zero(), -half.sin() ), ComplexNumber::new( half.cos(), T::zero() )]
         ])
     }
-    pub fn R_y ( theta: f32 ) -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: (theta / 2f32).cos(), b: 0f32 }, ComplexNumber { a: -(theta / 2f32).sin(), b: 0f32 }],
-            vec![ComplexNumber { a: (theta / 2f32).sin(), b: 0f32 }, ComplexNumber { a: (theta / 2f32).cos(), b: 0f32 }]
+    pub fn R_y ( theta: T ) -> Matrix<T> {
+        let half = theta / T::from_f64( 2.0 );
+
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::new( half.cos(), T::zero() ), ComplexNumber::new( -half.sin(), T::zero() )],
+            vec![ComplexNumber::new( half.sin(), T::zero() ), ComplexNumber::new( half.cos(), T::zero() )]
         ])
     }
-    pub fn R_z ( theta: f32 ) -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: (-theta / 2f32).cos(), b: (-theta / 2f32).sin() }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: (theta / 2f32).cos(), b: (theta / 2f32).sin() }]
+    pub fn R_z ( theta: T ) -> Matrix<T> {
+        let half = theta / T::from_f64( 2.0 );
+
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::new( (-half).cos(), (-half).sin() ), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), ComplexNumber::new( half.cos(), half.sin() )]
         ])
     }
-    pub fn R_1 ( theta: f32 ) -> Matrix {
-        Matrix::new(vec![
-            vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: theta.cos(), b: theta.sin() }]
+    pub fn R_1 ( theta: T ) -> Matrix<T> {
+        Matrix::from_rows(vec![
+            vec![ComplexNumber::one(), ComplexNumber::zero()],
+            vec![ComplexNumber::zero(), ComplexNumber::new( theta.cos(), theta.sin() )]
         ])
     }
 
 }
-/*
-mod complex;
-mod matrix;
-
-use complex::{
-    ComplexNumber,
-    //ComplexPolarNumber
-};
-use matrix::Matrix;
-
-fn main() {
-    /* Basic Functions */
-    // Matrice Multiplication
-    let mut matrix_1 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 3f32, b: 1f32}, ComplexNumber { a: 2f32, b: 1f32}],
-        vec![ ComplexNumber { a: 4f32, b: 3f32}, ComplexNumber { a: 1f32, b: 2f32}]]);
-
-    let matrix_2 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 8f32, b: 4f32}, ComplexNumber { a: 2f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 8f32, b: 4f32}, ComplexNumber { a: 2f32, b: 1f32} ]]);
-    
-    matrix_1 = matrix_1 * matrix_2;
-    println!("Multiplied: {:?}\n", matrix_1);
-
-
-    // Matrice Inversion
-    let mut matrix_3 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 1f32}, ComplexNumber { a: 0f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 0f32, b: 1f32}, ComplexNumber { a: 1f32, b: 0f32} ]]);
-
-    println!("Determinant: {:?}", matrix_3.determinant());
-
-    matrix_3.invert();
-    println!("Inversion: {:?}\n", matrix_3);
-
-
-    // Matrice Transposition
-    let mut matrix_4 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 1f32}, ComplexNumber { a: 0f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 1f32, b: 1f32}, ComplexNumber { a: 0f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 0f32, b: 1f32}, ComplexNumber { a: 1f32, b: 0f32} ]]);
-
-    matrix_4.transpose();
-    println!("Transposed: {:?}\n", matrix_4);
-
-
-    // Matrice Conjugation
-    let mut matrix_5 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 1f32}, ComplexNumber { a: 0f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 0f32, b: -1f32}, ComplexNumber { a: 1f32, b: 0f32} ]]);
-    
-    matrix_5.conjugate();
-    println!("Conjugated: {:?}\n", matrix_5);
-
-
-    // Matrice Adjunction
-    let mut matrix_6 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 1f32}, ComplexNumber { a: 0f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 0f32, b: -1f32}, ComplexNumber { a: 1f32, b: 0f32} ]]);
-    
-    matrix_6.adjunct();
-    println!("Adjuncted: {:?}\n", matrix_6);
-
-
-    // Unitary Verification
-    let mut matrix_7 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 0f32}, ComplexNumber { a: 0f32, b: 0f32} ],
-        vec![ ComplexNumber { a: 0f32, b: 0f32}, ComplexNumber { a: 1f32, b: 0f32} ]]);
-    
-    println!("Is unitary: {}\n", matrix_7.unitary());
-
-
-    /* Advanced Functions */
-    // Inner Product
-    let mut matrix_8 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 3f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 4f32, b: 3f32} ]]);
-
-    let matrix_9 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 8f32, b: 4f32} ],
-        vec![ ComplexNumber { a: 8f32, b: 4f32} ]]);
-    
-    println!("Inner product: {:?}\n", matrix_8.inner_product(&matrix_9));
-
-
-    // Normalize Matrix
-    let mut matrix_10 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 0f32}],
-        vec![ ComplexNumber { a: 1f32, b: 0f32}],
-        vec![ ComplexNumber { a: 1f32, b: 0f32}]]);
-    
-    println!("Normalized: {:?}\n", matrix_10.normalize());
-
-    
-    // Outer Product
-    let matrix_11 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 3f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 4f32, b: 3f32} ]]);
-
-    let matrix_12 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 8f32, b: 4f32} ],
-        vec![ ComplexNumber { a: 8f32, b: 4f32} ],
-        vec![ ComplexNumber { a: 8f32, b: 4f32} ]]);
-    
-    println!("Outer product: {:?}\n", matrix_11.outer_product(&matrix_12));
-
-
-
-    /* Further Advanced Functions */
-    // Tensor Product
-    let matrix_13 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 3f32, b: 1f32}, ComplexNumber { a: 2f32, b: 1f32}],
-        vec![ ComplexNumber { a: 4f32, b: 3f32}, ComplexNumber { a: 1f32, b: 2f32}]]);
-
-    let matrix_14 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 8f32, b: 4f32}, ComplexNumber { a: 2f32, b: 1f32} ],
-        vec![ ComplexNumber { a: 8f32, b: 4f32}, ComplexNumber { a: 2f32, b: 1f32} ]]);
-    
-    println!("Tensor Product: {:?}\n", matrix_13.tensor_product(&matrix_14));
-
-
-    // Procuring an eigenvalue from a base vector and an eigenvector
-    let matrix_14 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 0f32}, ComplexNumber { a: 0f32, b: 0f32}],
-        vec![ ComplexNumber { a: 0f32, b: 0f32}, ComplexNumber { a: 1f32, b: 0f32}]]);
-
-    let eigenvector_1 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 0f32} ],
-        vec![ ComplexNumber { a: 0f32, b: 0f32} ]]);
-    
-    println!("Eigenvalue: {:?}\n", matrix_14.eigenvalue_from_eigenvector( &eigenvector_1));
-
-
-    // Procuring an eigenvector from a base vector and an eigenvalue
-    let matrix_15 = Matrix::new(vec![
-        vec![ ComplexNumber { a: 1f32, b: 86f32}, ComplexNumber { a: 0f32, b: 28f32}],
-        vec![ ComplexNumber { a: 12f32, b: 23f32}, ComplexNumber { a: 1f32, b: 23f32}]]);
-
-    let eigenvalue_1 = ComplexNumber { a: 1f32, b: 18f32};
-    
-    println!("Eigenvalue: {:?}\n", matrix_15.eigenvector_from_eigenvalue( eigenvalue_1 ));
-}*/
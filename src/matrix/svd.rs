@@ -0,0 +1,180 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// Sweeps after which one-sided Jacobi gives up with NoConvergence, as for
+// the eigensolver
+const MAX_SWEEPS: usize = 64;
+
+impl<T: Float> Matrix<T> {
+    // Thin singular value decomposition A = U Σ V† of an m x n matrix, with
+    // k = min(m, n): U is m x k and V† is k x n, both with orthonormal rows
+    // or columns, and Σ is k x k, diagonal, real and descending.
+    pub fn svd ( &self ) -> Result<( Self, Self, Self )> {
+        if self.rows < self.cols {
+            // A† = V Σ U†
+            let mut adjunct = self.clone();
+            adjunct.adjunct();
+            let (v, sigma, mut u_adjunct) = adjunct.svd()?;
+            u_adjunct.adjunct();
+            let mut v_adjunct = v;
+            v_adjunct.adjunct();
+
+            return Ok(( u_adjunct, sigma, v_adjunct ));
+        }
+        let (m, n) = (self.rows, self.cols);
+
+        // One-sided Jacobi: rotate pairs of columns of W = A V until they're
+        // all orthogonal, at which point their lengths are the singular values
+        let mut w = self.clone();
+        let mut v = Self::identity( n );
+        let mut sweeps = 0;
+        loop {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    rotated |= orthogonalize( &mut w, &mut v, p, q );
+                }
+            }
+            if !rotated {
+                break;
+            }
+            sweeps += 1;
+            if sweeps == MAX_SWEEPS {
+                return Err(QuantumError::NoConvergence);
+            }
+        }
+
+        let lengths: Vec<T> = (0..n)
+            .map(|c| (0..m).map(|r| w[r][c].norm_sqr()).sum::<T>().sqrt())
+            .collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&x, &y| lengths[y].partial_cmp( &lengths[x] ).unwrap_or( std::cmp::Ordering::Equal ));
+
+        let largest = lengths[order[0]];
+        let mut u = Self::from_dimensions( m, n );
+        let mut sigma = Self::from_dimensions( n, n );
+        let mut v_adjunct = Self::from_dimensions( n, n );
+        let mut rank = 0;
+        for (k, &c) in order.iter().enumerate() {
+            sigma[k][k] = ComplexNumber::new( lengths[c], T::zero() );
+            for r in 0..n {
+                let v_rc = v[r][c];
+                v_adjunct[k][r] = v_rc.conj();
+            }
+            if lengths[c] > T::epsilon() * largest {
+                for r in 0..m {
+                    u[r][k] = w[r][c] / lengths[c];
+                }
+                rank += 1;
+            }
+        }
+        // Columns of U for zero singular values are arbitrary, as long as
+        // they stay orthonormal
+        complete_columns( &mut u, rank );
+
+        Ok(( u, sigma, v_adjunct ))
+    }
+    // The singular values alone, largest first
+    pub fn singular_values ( &self ) -> Result<Vec<T>> {
+        let (_, sigma, _) = self.svd()?;
+
+        Ok((0..sigma.rows).map(|k| sigma[k][k].a).collect())
+    }
+    // Polar decomposition A = W P of a square matrix into a unitary W and a
+    // positive semidefinite P. W = U V† is the unitary closest to A, which
+    // projects a gate that has drifted off unitarity back onto it.
+    pub fn polar ( &self ) -> Result<( Self, Self )> {
+        self.check_square()?;
+        let (u, sigma, v_adjunct) = self.svd()?;
+
+        let mut v = v_adjunct.clone();
+        v.adjunct();
+        Ok(( u * v_adjunct.clone(), v * sigma * v_adjunct ))
+    }
+}
+
+// Rotates columns p and q of w (and of v alongside) so that they become
+// orthogonal, with the same rotation the eigensolver applies to the Gram
+// matrix W†W. Returns whether they needed it.
+fn orthogonalize<T: Float> ( w: &mut Matrix<T>, v: &mut Matrix<T>, p: usize, q: usize ) -> bool {
+    let mut alpha = T::zero();
+    let mut beta = T::zero();
+    let mut gamma = ComplexNumber::zero();
+    for r in 0..w.rows {
+        let wp = w[r][p];
+        let wq = w[r][q];
+        alpha += wp.norm_sqr();
+        beta += wq.norm_sqr();
+        gamma += wp.conj() * wq;
+    }
+    let modulus = gamma.modulus();
+    if modulus <= T::epsilon() * ( alpha * beta ).sqrt() || modulus == T::zero() {
+        return false;
+    }
+    let phase = gamma / modulus;
+    let phase_bar = phase.conj();
+
+    let two = T::from_f64( 2.0 );
+    let tau = ( beta - alpha ) / ( two * modulus );
+    let t = if tau >= T::zero() {
+        T::one() / ( tau + ( T::one() + tau * tau ).sqrt() )
+    } else {
+        -T::one() / ( -tau + ( T::one() + tau * tau ).sqrt() )
+    };
+    let c = T::one() / ( T::one() + t * t ).sqrt();
+    let s = t * c;
+
+    let g_pp = ComplexNumber::new( c, T::zero() );
+    let g_pq = ComplexNumber::new( s, T::zero() );
+    let g_qp = phase_bar * -s;
+    let g_qq = phase_bar * c;
+    for m in [w, v] {
+        for r in 0..m.rows {
+            let mp = m[r][p];
+            let mq = m[r][q];
+            m[r][p] = mp * g_pp + mq * g_qp;
+            m[r][q] = mp * g_pq + mq * g_qq;
+        }
+    }
+    true
+}
+// Fills columns rank.. of u with unit vectors orthogonal to every column
+// before them, by Gram-Schmidt on the standard basis
+fn complete_columns<T: Float> ( u: &mut Matrix<T>, rank: usize ) {
+    let mut candidate = 0;
+    for k in rank..u.cols {
+        while candidate < u.rows {
+            let mut column = vec![ComplexNumber::zero(); u.rows];
+            column[candidate] = ComplexNumber::one();
+            candidate += 1;
+
+            for j in 0..k {
+                let overlap = (0..u.rows).fold(ComplexNumber::zero(), |acc, r| {
+                    acc + u[r][j].conj() * column[r]
+                });
+                for (r, entry) in column.iter_mut().enumerate() {
+                    *entry -= u[r][j] * overlap;
+                }
+            }
+            let length = column.iter().map(|entry| entry.norm_sqr()).sum::<T>().sqrt();
+            // What's left of a basis vector already mostly spanned is noise
+            if length > T::from_f64( 0.5 ) {
+                for (r, entry) in column.into_iter().enumerate() {
+                    u[r][k] = entry / length;
+                }
+                break;
+            }
+        }
+    }
+}
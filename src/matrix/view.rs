@@ -0,0 +1,152 @@
+use crate::{
+    complex,
+    error,
+    float
+};
+
+use super::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// Side of the square tiles the multiply works through, sized so a tile of
+// each operand stays in L1/L2 cache
+const BLOCK: usize = 64;
+
+// A borrowed, strided window onto a Matrix's storage: entry (r, c) lives at
+// data[offset + r * row_stride + c * col_stride]. Transposing or taking a
+// block only changes the strides and offset, never copies.
+#[derive(Clone, Copy)]
+pub struct MatrixView<'a, T: Float = f64> {
+    data: &'a [ComplexNumber<T>],
+    offset: usize,
+    row_stride: usize,
+    col_stride: usize,
+    pub rows: usize,
+    pub cols: usize
+}
+impl<'a, T: Float> MatrixView<'a, T> {
+    pub fn get ( &self, r: usize, c: usize ) -> ComplexNumber<T> {
+        self.data[self.offset + r * self.row_stride + c * self.col_stride]
+    }
+    pub fn transpose ( self ) -> Self {
+        Self {
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            rows: self.cols,
+            cols: self.rows,
+            ..self
+        }
+    }
+    // The rows x cols window whose top-left corner is (row, col)
+    pub fn block ( self, row: usize, col: usize, rows: usize, cols: usize ) -> Result<Self> {
+        if row + rows > self.rows || col + cols > self.cols {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, self.cols),
+                found: (row + rows, col + cols)
+            });
+        }
+
+        Ok(Self {
+            offset: self.offset + row * self.row_stride + col * self.col_stride,
+            rows,
+            cols,
+            ..self
+        })
+    }
+    pub fn row ( self, row: usize ) -> Result<Self> {
+        self.block( row, 0, 1, self.cols )
+    }
+    pub fn column ( self, col: usize ) -> Result<Self> {
+        self.block( 0, col, self.rows, 1 )
+    }
+    // Copies the window out into its own contiguous Matrix
+    pub fn to_matrix ( &self ) -> Matrix<T> {
+        let mut data = Vec::with_capacity( self.rows * self.cols );
+        for r in 0..self.rows {
+            data.extend( (0..self.cols).map(|c| self.get( r, c )) );
+        }
+
+        Matrix { data, rows: self.rows, cols: self.cols }
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    pub fn view ( &self ) -> MatrixView<'_, T> {
+        MatrixView {
+            data: &self.data,
+            offset: 0,
+            row_stride: self.cols,
+            col_stride: 1,
+            rows: self.rows,
+            cols: self.cols
+        }
+    }
+    // out = self * to_mul, reusing out's buffer instead of allocating a new
+    // matrix. out must already be self.rows x to_mul.cols.
+    pub fn mul_into ( &self, to_mul: &Self, out: &mut Self ) -> Result<()> {
+        self.view().mul_into( to_mul.view(), out )
+    }
+    pub(crate) fn swap_rows ( &mut self, a: usize, b: usize ) {
+        if a == b {
+            return;
+        }
+        let cols = self.cols;
+        let (low, high) = (a.min(b), a.max(b));
+        let (head, tail) = self.data.split_at_mut( high * cols );
+        head[low * cols..( low + 1 ) * cols].swap_with_slice( &mut tail[..cols] );
+    }
+}
+impl<T: Float> MatrixView<'_, T> {
+    // out = self * to_mul for views, so that e.g. A^T B never materializes
+    // the transpose
+    pub fn mul_into ( &self, to_mul: MatrixView<'_, T>, out: &mut Matrix<T> ) -> Result<()> {
+        if self.cols != to_mul.rows {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.cols, to_mul.cols),
+                found: (to_mul.rows, to_mul.cols)
+            });
+        }
+        if out.rows != self.rows || out.cols != to_mul.cols {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.rows, to_mul.cols),
+                found: (out.rows, out.cols)
+            });
+        }
+
+        out.data.fill( ComplexNumber::zero() );
+        gemm( *self, to_mul, out );
+        Ok(())
+    }
+}
+
+// out += a * b, for an out already sized a.rows x b.cols. Works through
+// BLOCK x BLOCK tiles in i-k-j order so the innermost loop runs along a row
+// of b and a row of out, both contiguous for an untransposed b. Zeros in
+// a are multiplied through like anything else, so 0 * NaN and 0 * ∞ still
+// come out NaN; sparse operands belong in SparseMatrix instead.
+pub(crate) fn gemm<T: Float> ( a: MatrixView<'_, T>, b: MatrixView<'_, T>, out: &mut Matrix<T> ) {
+    let n = out.cols;
+    for i_block in ( 0..a.rows ).step_by( BLOCK ) {
+        let i_end = ( i_block + BLOCK ).min( a.rows );
+        for k_block in ( 0..a.cols ).step_by( BLOCK ) {
+            let k_end = ( k_block + BLOCK ).min( a.cols );
+            for j_block in ( 0..b.cols ).step_by( BLOCK ) {
+                let j_end = ( j_block + BLOCK ).min( b.cols );
+
+                for i in i_block..i_end {
+                    let out_row = &mut out.data[i * n + j_block..i * n + j_end];
+                    for k in k_block..k_end {
+                        let scalar = a.get( i, k );
+                        for (j, entry) in out_row.iter_mut().enumerate() {
+                            *entry += scalar * b.get( k, j_block + j );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::{
+    matrix,
+    complex,
+    error,
+    float
+};
+
+use matrix::{
+    Matrix,
+    Gate
+};
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+// A quantum channel ρ -> Σ K ρ K†, given by its Kraus operators. Every
+// operator is 2^k x 2^k for a channel on k qubits, and together they satisfy
+// Σ K†K = I so that probability is conserved.
+#[derive(Clone, Debug)]
+pub struct Channel<T: Float = f64> {
+    kraus: Vec<Matrix<T>>
+}
+impl<T: Float> Channel<T> {
+    // Validates a user-supplied Kraus set
+    pub fn new ( kraus: Vec<Matrix<T>> ) -> Result<Self> {
+        let size = match kraus.first() {
+            Some(first) => first.rows,
+            None => return Err(QuantumError::MalformedMatrix)
+        };
+        if !size.is_power_of_two() {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (size.next_power_of_two(), size.next_power_of_two()),
+                found: (size, size)
+            });
+        }
+        for operator in &kraus {
+            if operator.rows != size || operator.cols != size {
+                return Err(QuantumError::DimensionMismatch {
+                    expected: (size, size),
+                    found: (operator.rows, operator.cols)
+                });
+            }
+        }
+
+        let completeness = kraus
+            .iter()
+            .fold(Matrix::from_dimensions( size, size ), |acc, operator| {
+                acc + &operator.dagger() * operator
+            });
+        if !completeness.approx_eq( &Matrix::identity( size ), T::tolerance() ) {
+            return Err(QuantumError::IncompleteKraus);
+        }
+
+        Ok(Self { kraus })
+    }
+
+    /* Standard single-qubit channels */
+    // X with probability p
+    pub fn bit_flip ( p: T ) -> Result<Self> {
+        check_probability( p )?;
+
+        Self::new(vec![
+            scaled( Gate::I(), T::one() - p ),
+            scaled( Gate::X(), p )
+        ])
+    }
+    // Z with probability p
+    pub fn phase_flip ( p: T ) -> Result<Self> {
+        check_probability( p )?;
+
+        Self::new(vec![
+            scaled( Gate::I(), T::one() - p ),
+            scaled( Gate::Z(), p )
+        ])
+    }
+    // X, Y or Z, each with probability p / 3
+    pub fn depolarizing ( p: T ) -> Result<Self> {
+        check_probability( p )?;
+
+        let third = p / T::from_f64( 3.0 );
+        Self::new(vec![
+            scaled( Gate::I(), T::one() - p ),
+            scaled( Gate::X(), third ),
+            scaled( Gate::Y(), third ),
+            scaled( Gate::Z(), third )
+        ])
+    }
+    // Decay of |1> to |0> with probability gamma, as in T1 relaxation
+    pub fn amplitude_damping ( gamma: T ) -> Result<Self> {
+        check_probability( gamma )?;
+
+        Self::new(vec![
+            Matrix::from_rows(vec![
+                vec![ComplexNumber::one(), ComplexNumber::zero()],
+                vec![ComplexNumber::zero(), ComplexNumber::new( ( T::one() - gamma ).sqrt(), T::zero() )]
+            ]),
+            Matrix::from_rows(vec![
+                vec![ComplexNumber::zero(), ComplexNumber::new( gamma.sqrt(), T::zero() )],
+                vec![ComplexNumber::zero(), ComplexNumber::zero()]
+            ])
+        ])
+    }
+    // Loss of coherence without loss of energy, as in T2 dephasing
+    pub fn phase_damping ( lambda: T ) -> Result<Self> {
+        check_probability( lambda )?;
+
+        Self::new(vec![
+            Matrix::from_rows(vec![
+                vec![ComplexNumber::one(), ComplexNumber::zero()],
+                vec![ComplexNumber::zero(), ComplexNumber::new( ( T::one() - lambda ).sqrt(), T::zero() )]
+            ]),
+            Matrix::from_rows(vec![
+                vec![ComplexNumber::zero(), ComplexNumber::zero()],
+                vec![ComplexNumber::zero(), ComplexNumber::new( lambda.sqrt(), T::zero() )]
+            ])
+        ])
+    }
+
+    pub fn kraus ( &self ) -> &[Matrix<T>] {
+        &self.kraus
+    }
+    // Number of qubits the channel acts on
+    pub fn qubits ( &self ) -> usize {
+        self.kraus[0].rows.trailing_zeros() as usize
+    }
+}
+
+// Which channels a System applies after each gate, and how its measurements
+// are misread. Gates are named as the System methods that apply them: "H",
+// "S_adj", "R_x", "CNOT", "CR_z", ... with "I" for idling, "apply" for System::apply
+// and "controlled" for System::controlled/controlled_on.
+//
+// A single-qubit channel hits every qubit the gate touched; a k-qubit channel
+// hits a k-qubit gate's qubits as a whole and is skipped for other widths.
+#[derive(Clone, Debug)]
+pub struct NoiseModel<T: Float = f64> {
+    after_gate: HashMap<String, Channel<T>>,
+    // Keyed by the unordered pair, stored smallest first
+    after_pair: HashMap<(usize, usize), Channel<T>>,
+    after_two_qubit_gate: Option<Channel<T>>,
+    // readout[k][read][actual]: chance of reading `read` when qubit k is `actual`
+    readout: HashMap<usize, [[f64; 2]; 2]>
+}
+impl<T: Float> Default for NoiseModel<T> {
+    fn default () -> Self {
+        Self::new()
+    }
+}
+impl<T: Float> NoiseModel<T> {
+    pub fn new () -> Self {
+        Self {
+            after_gate: HashMap::new(),
+            after_pair: HashMap::new(),
+            after_two_qubit_gate: None,
+            readout: HashMap::new()
+        }
+    }
+
+    // After every gate called `gate`, on whichever qubits it ran
+    pub fn after_gate ( mut self, gate: &str, channel: Channel<T> ) -> Self {
+        self.after_gate.insert( gate.to_string(), channel );
+        self
+    }
+    // After every two-qubit gate, unless after_pair() covers its qubits
+    pub fn after_two_qubit_gates ( mut self, channel: Channel<T> ) -> Self {
+        self.after_two_qubit_gate = Some(channel);
+        self
+    }
+    // After every two-qubit gate on qubits i and j, in either order
+    pub fn after_pair ( mut self, i: usize, j: usize, channel: Channel<T> ) -> Self {
+        self.after_pair.insert( ( i.min(j), i.max(j) ), channel );
+        self
+    }
+    // Readout confusion matrix for qubit k, where matrix[read][actual] is the
+    // chance of reading `read` when the qubit was measured as `actual`.
+    // Each column must be a probability distribution.
+    pub fn readout_error ( mut self, qubit: usize, matrix: [[f64; 2]; 2] ) -> Result<Self> {
+        for actual in 0..2 {
+            for read in matrix {
+                check_probability( read[actual] )?;
+            }
+            let total = matrix[0][actual] + matrix[1][actual];
+            if ( total - 1f64 ).abs() > 1e-9 {
+                return Err(QuantumError::InvalidProbability( total ));
+            }
+        }
+
+        self.readout.insert( qubit, matrix );
+        Ok(self)
+    }
+    // Symmetric readout error: each outcome is misread with probability p
+    pub fn readout_flip ( self, qubit: usize, p: f64 ) -> Result<Self> {
+        self.readout_error( qubit, [[1f64 - p, p], [p, 1f64 - p]] )
+    }
+
+    // The channels due after `gate` ran on `qubits`, with where each goes
+    pub(crate) fn channels_after ( &self, gate: &str, qubits: &[usize] ) -> Vec<(&Channel<T>, Vec<usize>)> {
+        let mut channels = Vec::new();
+        if let Some(channel) = self.after_gate.get( gate ) {
+            channels.push( channel );
+        }
+        if let [i, j] = *qubits {
+            match self.after_pair.get( &( i.min(j), i.max(j) ) ) {
+                Some(channel) => channels.push( channel ),
+                None => channels.extend( self.after_two_qubit_gate.as_ref() )
+            }
+        }
+
+        let mut placed = Vec::new();
+        for channel in channels {
+            if channel.qubits() == 1 {
+                placed.extend( qubits.iter().map(|&qubit| (channel, vec![qubit])) );
+            } else if channel.qubits() == qubits.len() {
+                placed.push( (channel, qubits.to_vec()) );
+            }
+        }
+        placed
+    }
+    pub(crate) fn readout ( &self, qubit: usize ) -> Option<&[[f64; 2]; 2]> {
+        self.readout.get( &qubit )
+    }
+}
+
+fn check_probability<T: Float> ( p: T ) -> Result<()> {
+    if !( p >= T::zero() && p <= T::one() ) {
+        return Err(QuantumError::InvalidProbability( p.to_f64() ));
+    }
+    Ok(())
+}
+// sqrt(p) * gate, the Kraus operator for a gate applied with probability p
+fn scaled<T: Float> ( gate: Matrix<T>, p: T ) -> Matrix<T> {
+    gate * ComplexNumber::new( p.sqrt(), T::zero() )
+}
@@ -1,105 +1,113 @@
 use crate::{
     matrix,
-    complex
+    complex,
+    error,
+    float
 };
 
 use matrix::Matrix;
 use matrix::Gate;
 use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
 
-const ONE_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0.707106781f32, b: 0f32 };
-const I_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0f32, b: 0.707106781f32 };
-const NEG_ONE_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: -0.707106781f32, b: 0f32 };
-const NEG_I_OVER_SQRT_TWO: ComplexNumber = ComplexNumber { a: 0f32, b: -0.707106781f32 };
-
-const SQRT_TWO_OVER_TWO: f32 = std::f32::consts::SQRT_2 / 2f32;
-
-pub struct Qubit {
-    pub state: Matrix
+// (a + bi) / sqrt(2), for the superposition kets
+fn over_sqrt_two<T: Float> ( a: T, b: T ) -> ComplexNumber<T> {
+    ComplexNumber::new( a, b ) * T::frac_1_sqrt_2()
 }
-impl std::ops::Mul<Qubit> for Qubit {
-    type Output = Matrix;
 
-    fn mul ( self, to_mul: Qubit ) -> Matrix {
-        let mut state = self.state.clone();
+pub struct Qubit<T: Float = f64> {
+    pub state: Matrix<T>
+}
+impl<T: Float> std::ops::Mul<Qubit<T>> for Qubit<T> {
+    type Output = Matrix<T>;
 
-        state * to_mul.state
+    fn mul ( self, to_mul: Qubit<T> ) -> Matrix<T> {
+        self.state * to_mul.state
     }
 }
-impl std::fmt::Debug for Qubit {
+impl<T: Float> std::fmt::Debug for Qubit<T> {
     fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
-        write!(f, "Should only be used for debugging! Use dump() or measure()!\n")?;
+        writeln!(f, "Should only be used for debugging! Use dump() or measure()!")?;
         self.measure();
         Ok(())
     }
 }
-impl Qubit {
-    pub fn ket ( id: &str ) -> Self {
-        return match id {
+impl<T: Float> Default for Qubit<T> {
+    fn default () -> Self {
+        Self::new()
+    }
+}
+impl<T: Float> Qubit<T> {
+    pub fn ket ( id: &str ) -> Result<Self> {
+        Ok(match id {
             "ZERO" => Self{
-                state: Matrix::new(vec![
-                    vec![ComplexNumber { a: 1f32, b: 0f32 }],
-                    vec![ComplexNumber { a: 0f32, b: 0f32 }]
+                state: Matrix::from_rows(vec![
+                    vec![ComplexNumber::one()],
+                    vec![ComplexNumber::zero()]
                 ])
             },
             "ONE" => Self{
-                state: Matrix::new(vec![
-                    vec![ComplexNumber { a: 0f32, b: 0f32 }],
-                    vec![ComplexNumber { a: 1f32, b: 0f32 }]
+                state: Matrix::from_rows(vec![
+                    vec![ComplexNumber::zero()],
+                    vec![ComplexNumber::one()]
                 ])
             },
             "PLUS" => Self{
-                state: Matrix::new(vec![
-                    vec![ONE_OVER_SQRT_TWO],
-                    vec![ONE_OVER_SQRT_TWO]
+                state: Matrix::from_rows(vec![
+                    vec![over_sqrt_two( T::one(), T::zero() )],
+                    vec![over_sqrt_two( T::one(), T::zero() )]
                 ])
             },
             "NEG" => Self{
-                state: Matrix::new(vec![
-                    vec![ONE_OVER_SQRT_TWO],
-                    vec![NEG_ONE_OVER_SQRT_TWO]
+                state: Matrix::from_rows(vec![
+                    vec![over_sqrt_two( T::one(), T::zero() )],
+                    vec![over_sqrt_two( -T::one(), T::zero() )]
                 ])
             },
             "I" => Self{
-                state: Matrix::new(vec![
-                    vec![ONE_OVER_SQRT_TWO],
-                    vec![I_OVER_SQRT_TWO]
+                state: Matrix::from_rows(vec![
+                    vec![over_sqrt_two( T::one(), T::zero() )],
+                    vec![over_sqrt_two( T::zero(), T::one() )]
                 ])
             },
             "NEG_I" => Self{
-                state: Matrix::new(vec![
-                    vec![ONE_OVER_SQRT_TWO],
-                    vec![NEG_I_OVER_SQRT_TWO]
+                state: Matrix::from_rows(vec![
+                    vec![over_sqrt_two( T::one(), T::zero() )],
+                    vec![over_sqrt_two( T::zero(), -T::one() )]
                 ])
             },
-            _ => panic!("Not a valid or recognized ket state!")
-        }
+            _ => return Err(QuantumError::UnknownState( id.to_string() ))
+        })
     }
 
     pub fn measure ( &self ) {
-        let alpha_combined = self.state[0][0].a.clone().powi(2) + self.state[0][0].b.clone().powi(2);
-        let beta_combined = self.state[1][0].a.clone().powi(2) + self.state[1][0].b.clone().powi(2);
+        let alpha_combined = self.state[0][0].norm_sqr().to_f64();
+        let beta_combined = self.state[1][0].norm_sqr().to_f64();
 
-        let alpha_bar = String::from("#".repeat((alpha_combined * 20.).round() as usize)) + &"_".repeat((20. - (alpha_combined * 20.).round()) as usize);
-        let beta_bar = String::from("#".repeat((beta_combined * 20.).round() as usize)) + &"_".repeat((20. - (beta_combined * 20.).round()) as usize);
+        let alpha_bar = "#".repeat((alpha_combined * 20.).round() as usize) + &"_".repeat((20. - (alpha_combined * 20.).round()) as usize);
+        let beta_bar = "#".repeat((beta_combined * 20.).round() as usize) + &"_".repeat((20. - (beta_combined * 20.).round()) as usize);
 
         let alpha_phase = self.state[0][0]
-            .clone().polar()
+            .polar()
             .theta
             .to_degrees();
         let beta_phase = self.state[1][0]
-            .clone().polar()
+            .polar()
             .theta
             .to_degrees();
         
-        println!("Chance of being measured as on: {}%", ((self.state[1][0].clone() * self.state[1][0].clone()).modulus()  * 1000000f32).round() / 10000f32);
+        println!("Chance of being measured as on: {}%", ((self.state[1][0] * self.state[1][0]).modulus().to_f64() * 1000000f64).round() / 10000f64);
         println!("|0> {:?} | {} | {}°\n|1> {:?} | {} | {}°\n", self.state[0][0], alpha_bar, alpha_phase, self.state[1][0], beta_bar, beta_phase );
     }
     pub fn new () -> Self {
         Self {
-            state: Matrix::new(vec![
-                vec![ComplexNumber { a: 1f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }]
+            state: Matrix::from_rows(vec![
+                vec![ComplexNumber::one()],
+                vec![ComplexNumber::zero()]
             ])
         }
     }
@@ -117,19 +125,19 @@ impl Qubit {
     // X Gate - 'Not' Gate
     #[allow(non_snake_case)]
     pub fn X ( &mut self ) -> &Self {
-        self.state = Gate::X() * self.state.clone();
+        self.state = &Gate::X() * &self.state;
         self
     }
     // Y Gate - 'TODO'
     #[allow(non_snake_case)]
     pub fn Y ( &mut self ) -> &Self {
-        self.state = Gate::Y() * self.state.clone();
+        self.state = &Gate::Y() * &self.state;
         self
     }
     // Z Gate - 'TODO'
     #[allow(non_snake_case)]
     pub fn Z ( &mut self ) -> &Self {
-        self.state = Gate::Z() * self.state.clone();
+        self.state = &Gate::Z() * &self.state;
         self
     }
 
@@ -137,7 +145,7 @@ impl Qubit {
     // H Gate - 'Superposition' gate
     #[allow(non_snake_case)]
     pub fn H ( &mut self ) -> &Self {
-        self.state = Gate::H() * self.state.clone();
+        self.state = &Gate::H() * &self.state;
         self
     }
 
@@ -145,39 +153,39 @@ impl Qubit {
     // S Gate - 'i phase flip'
     #[allow(non_snake_case)]
     pub fn S ( &mut self ) -> &Self {
-        self.state = Gate::S() * self.state.clone();
+        self.state = &Gate::S() * &self.state;
         self
     }
     // T Gate - '45 deg' (might be wrong)
     #[allow(non_snake_case)]
     pub fn T ( &mut self ) -> &Self {
-        self.state = Gate::T() * self.state.clone();
+        self.state = &Gate::T() * &self.state;
         self
     }
 
     /* - Rotation Gates - */
     // R sub x gate - 'X rotation'
     #[allow(non_snake_case)]
-    pub fn R_x( &mut self, theta: f32 ) -> &Self {
-        self.state = Gate::R_x(theta) * self.state.clone();
+    pub fn R_x( &mut self, theta: T ) -> &Self {
+        self.state = &Gate::R_x(theta) * &self.state;
         self
     }
     // R sub y gate - 'Y rotation'
     #[allow(non_snake_case)]
-    pub fn R_y( &mut self, theta: f32 ) -> &Self {
-        self.state = Gate::R_y( theta ) * self.state.clone();
+    pub fn R_y( &mut self, theta: T ) -> &Self {
+        self.state = &Gate::R_y( theta ) * &self.state;
         self
     }
     // R sub z gate - 'Z rotation'
     #[allow(non_snake_case)]
-    pub fn R_z( &mut self, theta: f32 ) -> &Self {
-        self.state = Gate::R_z( theta ) * self.state.clone();
+    pub fn R_z( &mut self, theta: T ) -> &Self {
+        self.state = &Gate::R_z( theta ) * &self.state;
         self
     }
     // R sub 1 gate - 'Arbitrary phase gate'
     #[allow(non_snake_case)]
-    pub fn R_1( &mut self, theta: f32 ) -> &Self {
-        self.state = Gate::R_1( theta ) * self.state.clone();
+    pub fn R_1( &mut self, theta: T ) -> &Self {
+        self.state = &Gate::R_1( theta ) * &self.state;
         self
     }
 }
@@ -0,0 +1,44 @@
+use std::hash::{
+    BuildHasher,
+    Hasher
+};
+
+// Source of uniform randomness used to sample measurement outcomes.
+// Implement this to drive a System from your own generator.
+pub trait Rng {
+    fn next_u64 ( &mut self ) -> u64;
+
+    // Uniform float in [0, 1)
+    fn next_f64 ( &mut self ) -> f64 {
+        ( self.next_u64() >> 11 ) as f64 / ( 1u64 << 53 ) as f64
+    }
+}
+
+// SplitMix64 - small, fast and fully determined by its seed
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64
+}
+impl SeededRng {
+    pub fn new ( seed: u64 ) -> Self {
+        Self { state: seed }
+    }
+    pub fn from_entropy () -> Self {
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        if let Ok(elapsed) = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            hasher.write_u128( elapsed.as_nanos() );
+        }
+
+        Self::new( hasher.finish() )
+    }
+}
+impl Rng for SeededRng {
+    fn next_u64 ( &mut self ) -> u64 {
+        self.state = self.state.wrapping_add( 0x9E3779B97F4A7C15 );
+
+        let mut z = self.state;
+        z = ( z ^ ( z >> 30 ) ).wrapping_mul( 0xBF58476D1CE4E5B9 );
+        z = ( z ^ ( z >> 27 ) ).wrapping_mul( 0x94D049BB133111EB );
+        z ^ ( z >> 31 )
+    }
+}
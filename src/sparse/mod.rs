@@ -0,0 +1,251 @@
+use crate::{
+    matrix,
+    complex,
+    error,
+    float
+};
+
+use matrix::Matrix;
+use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+
+use std::ops::Mul;
+
+// A complex matrix in compressed sparse row (CSR) form, holding only its
+// non-zero entries. The entries of row r are values[row_offsets[r]..row_offsets[r + 1]],
+// in columns col_indices[..] over the same range, sorted by column.
+//
+// Operators such as I ⊗ ... ⊗ X ⊗ ... ⊗ I on n qubits have 2^n non-zeros
+// rather than 4^n, which is what makes full-system operators past a dozen
+// qubits fit in memory.
+#[derive(Clone, PartialEq)]
+pub struct SparseMatrix<T: Float = f64> {
+    pub rows: usize,
+    pub cols: usize,
+    row_offsets: Vec<usize>,
+    col_indices: Vec<usize>,
+    values: Vec<ComplexNumber<T>>
+}
+impl<T: Float> std::fmt::Debug for SparseMatrix<T> {
+    fn fmt ( &self, f: &mut std::fmt::Formatter<'_> ) -> std::fmt::Result {
+        writeln!(f, "Sparse Complex Matrix: {}x{}, {} non-zero", self.rows, self.cols, self.nnz() )?;
+        for (r, c, value) in self.triplets() {
+            writeln!(f, "({}, {}) {:?}", r, c, value )?;
+        }
+        Ok(())
+    }
+}
+impl<T: Float> From<&Matrix<T>> for SparseMatrix<T> {
+    fn from ( dense: &Matrix<T> ) -> Self {
+        let mut ret = Self::empty( dense.rows, dense.cols );
+        for r in 0..dense.rows {
+            for (c, &value) in dense[r].iter().enumerate() {
+                if value != ComplexNumber::zero() {
+                    ret.col_indices.push( c );
+                    ret.values.push( value );
+                }
+            }
+            ret.row_offsets.push( ret.values.len() );
+        }
+        ret
+    }
+}
+impl<T: Float> From<&SparseMatrix<T>> for Matrix<T> {
+    fn from ( sparse: &SparseMatrix<T> ) -> Self {
+        sparse.to_dense()
+    }
+}
+// Sparse times dense, for state vectors or whole dense matrices
+impl<T: Float> Mul<&Matrix<T>> for &SparseMatrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul ( self, to_mul: &Matrix<T> ) -> Matrix<T> {
+        if self.cols != to_mul.rows {
+            panic!("Number of columns in the base matrix must match the number of rows in the second matrix!");
+        }
+
+        let mut ret = Matrix::from_dimensions( self.rows, to_mul.cols );
+        for r in 0..self.rows {
+            for (c, value) in self.row( r ) {
+                for (entry, &x) in ret[r].iter_mut().zip( &to_mul[c] ) {
+                    *entry += value * x;
+                }
+            }
+        }
+        ret
+    }
+}
+impl<T: Float> Mul<&SparseMatrix<T>> for &SparseMatrix<T> {
+    type Output = SparseMatrix<T>;
+
+    fn mul ( self, to_mul: &SparseMatrix<T> ) -> SparseMatrix<T> {
+        if self.cols != to_mul.rows {
+            panic!("Number of columns in the base matrix must match the number of rows in the second matrix!");
+        }
+
+        // Row by row, scattering into a dense accumulator and remembering
+        // which of its columns were touched
+        let mut ret = SparseMatrix::empty( self.rows, to_mul.cols );
+        let mut accumulator = vec![ComplexNumber::zero(); to_mul.cols];
+        let mut touched = vec![false; to_mul.cols];
+        let mut columns = Vec::new();
+        for r in 0..self.rows {
+            for (k, scalar) in self.row( r ) {
+                for (c, value) in to_mul.row( k ) {
+                    if !touched[c] {
+                        touched[c] = true;
+                        columns.push( c );
+                    }
+                    accumulator[c] += scalar * value;
+                }
+            }
+
+            columns.sort_unstable();
+            for &c in &columns {
+                if accumulator[c] != ComplexNumber::zero() {
+                    ret.col_indices.push( c );
+                    ret.values.push( accumulator[c] );
+                }
+                accumulator[c] = ComplexNumber::zero();
+                touched[c] = false;
+            }
+            columns.clear();
+            ret.row_offsets.push( ret.values.len() );
+        }
+        ret
+    }
+}
+impl<T: Float> SparseMatrix<T> {
+    fn empty ( rows: usize, cols: usize ) -> Self {
+        Self {
+            rows,
+            cols,
+            row_offsets: vec![0],
+            col_indices: Vec::new(),
+            values: Vec::new()
+        }
+    }
+    pub fn identity ( size: usize ) -> Self {
+        Self {
+            rows: size,
+            cols: size,
+            row_offsets: (0..=size).collect(),
+            col_indices: (0..size).collect(),
+            values: vec![ComplexNumber::one(); size]
+        }
+    }
+    // Builds from coordinate (COO) triplets (row, col, value) in any order.
+    // Repeated coordinates are summed and zeros are dropped.
+    pub fn from_triplets ( rows: usize, cols: usize, mut triplets: Vec<(usize, usize, ComplexNumber<T>)> ) -> Result<Self> {
+        if let Some(&(r, c, _)) = triplets.iter().find(|(r, c, _)| *r >= rows || *c >= cols) {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (rows, cols),
+                found: (r + 1, c + 1)
+            });
+        }
+        triplets.sort_by_key(|&(r, c, _)| (r, c));
+
+        let mut ret = Self::empty( rows, cols );
+        let mut triplets = triplets.into_iter().peekable();
+        for r in 0..rows {
+            while let Some(&(_, c, _)) = triplets.peek().filter(|&&(row, _, _)| row == r) {
+                let mut sum = ComplexNumber::zero();
+                while let Some((_, _, value)) = triplets.next_if(|&(row, col, _)| (row, col) == (r, c)) {
+                    sum += value;
+                }
+                if sum != ComplexNumber::zero() {
+                    ret.col_indices.push( c );
+                    ret.values.push( sum );
+                }
+            }
+            ret.row_offsets.push( ret.values.len() );
+        }
+        Ok(ret)
+    }
+    // The non-zero entries as (row, col, value), in row-major order
+    pub fn triplets ( &self ) -> Vec<(usize, usize, ComplexNumber<T>)> {
+        (0..self.rows)
+            .flat_map(|r| self.row( r ).map(move |(c, value)| (r, c, value)))
+            .collect()
+    }
+    pub fn to_dense ( &self ) -> Matrix<T> {
+        let mut ret = Matrix::from_dimensions( self.rows, self.cols );
+        for (r, c, value) in self.triplets() {
+            ret[r][c] = value;
+        }
+        ret
+    }
+
+    // Number of stored (non-zero) entries
+    pub fn nnz ( &self ) -> usize {
+        self.values.len()
+    }
+    pub fn get ( &self, r: usize, c: usize ) -> ComplexNumber<T> {
+        let range = self.row_offsets[r]..self.row_offsets[r + 1];
+        match self.col_indices[range.clone()].binary_search( &c ) {
+            Ok(position) => self.values[range.start + position],
+            Err(_) => ComplexNumber::zero()
+        }
+    }
+    // (col, value) for each non-zero entry of row r
+    pub fn row ( &self, r: usize ) -> impl Iterator<Item = (usize, ComplexNumber<T>)> + '_ {
+        let range = self.row_offsets[r]..self.row_offsets[r + 1];
+        self.col_indices[range.clone()].iter().copied().zip( self.values[range].iter().copied() )
+    }
+
+    // Non-panicking versions of the products
+    pub fn checked_mul ( &self, to_mul: &Self ) -> Result<Self> {
+        self.check_product( to_mul.rows, to_mul.cols )?;
+        Ok(self * to_mul)
+    }
+    pub fn checked_mul_dense ( &self, to_mul: &Matrix<T> ) -> Result<Matrix<T>> {
+        self.check_product( to_mul.rows, to_mul.cols )?;
+        Ok(self * to_mul)
+    }
+    // A |ψ> for a state given as plain amplitudes, without building a Matrix
+    pub fn mul_vector ( &self, amplitudes: &[ComplexNumber<T>] ) -> Result<Vec<ComplexNumber<T>>> {
+        self.check_product( amplitudes.len(), 1 )?;
+
+        Ok((0..self.rows)
+            .map(|r| self.row( r ).fold(ComplexNumber::zero(), |acc, (c, value)| acc + value * amplitudes[c]))
+            .collect())
+    }
+    fn check_product ( &self, rows: usize, cols: usize ) -> Result<()> {
+        if self.cols != rows {
+            return Err(QuantumError::DimensionMismatch {
+                expected: (self.cols, cols),
+                found: (rows, cols)
+            });
+        }
+        Ok(())
+    }
+
+    // A ⊗ B, with nnz(A) * nnz(B) entries and no dense intermediate
+    pub fn tensor_product ( &self, to_mul: &Self ) -> Self {
+        let mut ret = Self::empty( self.rows * to_mul.rows, self.cols * to_mul.cols );
+        for r in 0..self.rows {
+            for r_n in 0..to_mul.rows {
+                for (c, scalar) in self.row( r ) {
+                    for (c_n, value) in to_mul.row( r_n ) {
+                        ret.col_indices.push( c * to_mul.cols + c_n );
+                        ret.values.push( scalar * value );
+                    }
+                }
+                ret.row_offsets.push( ret.values.len() );
+            }
+        }
+        ret
+    }
+    pub fn adjunct ( &self ) -> Self {
+        let triplets = self.triplets()
+            .into_iter()
+            .map(|(r, c, value)| (c, r, value.conj()))
+            .collect();
+
+        Self::from_triplets( self.cols, self.rows, triplets ).expect("Transposed indices are in range")
+    }
+}
@@ -1,216 +1,1063 @@
+mod kernels;
+mod split;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
 use crate::{
     matrix,
     complex,
-    qubit
+    error,
+    qubit,
+    random,
+    noise,
+    sparse,
+    float
 };
 use qubit::Qubit;
+use noise::{
+    Channel,
+    NoiseModel
+};
+use random::{
+    Rng,
+    SeededRng
+};
 use matrix::Matrix;
 use matrix::Gate;
+use matrix::trace_out;
+use sparse::SparseMatrix;
 use complex::ComplexNumber;
+use error::{
+    QuantumError,
+    Result
+};
+use float::Float;
+use kernels::{
+    apply_controlled_to_amplitudes,
+    apply_to_amplitudes
+};
+use split::SplitState;
 
-pub struct System {
-    state: Vec<StateEntry>,
+// Full n-qubit state. Qubit 0 is the most significant (leftmost) factor of
+// the tensor product, matching the order qubits are allocated in.
+// Amplitudes are stored in precision T; System::new() builds an f64 system,
+// while System::<f32>::default() or System::<f32>::from_backend() give single
+// precision.
+pub struct System<T: Float = f64> {
+    // The 2^n amplitudes, or with the density matrix backend the 4^n entries
+    // of ρ, where ρ[r][c] lives at (r << n) | c. Empty with the
+    // split-complex backend, whose amplitudes live in `split` instead.
+    state: Vec<ComplexNumber<T>>,
+    split: SplitState<T>,
+    qubits: usize,
+    backend: Backend,
+    noise: Option<NoiseModel<T>>,
+    rng: Box<dyn Rng + Send>
 }
-pub enum StateEntry {
-    StandardQubit(Qubit),
-    EntangledState(Matrix),
-    EntangledStatePtr(usize)
+
+// How a System stores its state, chosen when it is constructed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    // Pure states only; 2^n amplitudes
+    StateVector,
+    // Mixed states; 4^n entries. Gates act as ρ -> UρU†
+    DensityMatrix,
+    // Pure states only; 2^n amplitudes with their real and imaginary parts
+    // in separate arrays, so single-qubit and controlled gates vectorize
+    SplitComplex
 }
-impl StateEntry {
-    pub fn unwrap_qubit ( &mut self ) -> &mut Qubit {
-        match self {
-            StateEntry::StandardQubit(ref mut q) => { return q; },
-            StateEntry::EntangledState(_) | StateEntry::EntangledStatePtr(_) => { panic!("Is entangled! Not a lone qubit."); },
-        }
-    }
+
+// Single-qubit measurement bases
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z
+}
+
+// Handle to a single register of a System, returned by allocate() and qubit().
+// The index is validated when the handle is made, so its gates cannot fail.
+pub struct QubitRef<'a, T: Float = f64> {
+    system: &'a mut System<T>,
+    index: usize
 }
+impl<T: Float> QubitRef<'_, T> {
+    pub fn index ( &self ) -> usize {
+        self.index
+    }
+    pub fn dump ( &self ) {
+        self.system.print_register( self.index );
+    }
+    pub fn measure ( &mut self ) -> bool {
+        let mask = self.system.bit( self.index );
+        let outcome = self.system.measure_parity( mask );
+        self.system.read_out( self.index, outcome )
+    }
+
+    /* Gate Operations */
+    /* - IDENTITY GATE - */
+    // Identity Gate - 'No effect' Gate
+    #[allow(non_snake_case)]
+    pub fn I ( &mut self ) -> &mut Self {
+        // Nothing happens to the state, but an idle qubit still picks up noise
+        self.system.after_gate( "I", &[self.index] );
+        self
+    }
 
-impl std::ops::Index<usize> for System {
-    type Output = StateEntry;
+    /* - PAULI GATES - */
+    // X Gate - 'Not' Gate
+    #[allow(non_snake_case)]
+    pub fn X ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "X", &Gate::X(), self.index );
+        self
+    }
+    // Y Gate - 'Bit and phase flip'
+    #[allow(non_snake_case)]
+    pub fn Y ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "Y", &Gate::Y(), self.index );
+        self
+    }
+    // Z Gate - 'Phase flip'
+    #[allow(non_snake_case)]
+    pub fn Z ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "Z", &Gate::Z(), self.index );
+        self
+    }
 
-    fn index ( &self, index: usize ) -> &Self::Output {
-        &self.state[index]
+    /* - Hadamard Gates - */
+    // H Gate - 'Superposition' gate
+    #[allow(non_snake_case)]
+    pub fn H ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "H", &Gate::H(), self.index );
+        self
+    }
+
+    /* - Phase Shift Gates - */
+    // S Gate - 'i phase flip'
+    #[allow(non_snake_case)]
+    pub fn S ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "S", &Gate::S(), self.index );
+        self
+    }
+    // T Gate - '45 deg'
+    #[allow(non_snake_case)]
+    pub fn T ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "T", &Gate::T(), self.index );
+        self
+    }
+    // S† and T†, the adjoints of the above
+    #[allow(non_snake_case)]
+    pub fn S_adj ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "S_adj", &Gate::S_adj(), self.index );
+        self
+    }
+    #[allow(non_snake_case)]
+    pub fn T_adj ( &mut self ) -> &mut Self {
+        self.system.apply_gate( "T_adj", &Gate::T_adj(), self.index );
+        self
+    }
+
+    /* - Rotation Gates - */
+    // R sub x gate - 'X rotation'
+    #[allow(non_snake_case)]
+    pub fn R_x ( &mut self, theta: T ) -> &mut Self {
+        self.system.apply_gate( "R_x", &Gate::R_x( theta ), self.index );
+        self
+    }
+    // R sub y gate - 'Y rotation'
+    #[allow(non_snake_case)]
+    pub fn R_y ( &mut self, theta: T ) -> &mut Self {
+        self.system.apply_gate( "R_y", &Gate::R_y( theta ), self.index );
+        self
+    }
+    // R sub z gate - 'Z rotation'
+    #[allow(non_snake_case)]
+    pub fn R_z ( &mut self, theta: T ) -> &mut Self {
+        self.system.apply_gate( "R_z", &Gate::R_z( theta ), self.index );
+        self
+    }
+    // R sub 1 gate - 'Arbitrary phase gate'
+    #[allow(non_snake_case)]
+    pub fn R_1 ( &mut self, theta: T ) -> &mut Self {
+        self.system.apply_gate( "R_1", &Gate::R_1( theta ), self.index );
+        self
     }
 }
-impl std::ops::IndexMut<usize> for System {
-    fn index_mut ( &mut self, index: usize ) -> &mut Self::Output {
-        &mut self.state[index]
+
+impl<T: Float> Default for System<T> {
+    fn default () -> Self {
+        Self::from_backend( Backend::StateVector, SeededRng::from_entropy() )
     }
 }
+// f64 constructors, so that e.g. System::with_backend( .. ) needs no type
+// annotation. Other precisions go through default() or from_backend().
 impl System {
-    pub fn dump ( self ) {
+    pub fn new () -> Self {
+        Self::default()
+    }
+    pub fn seeded ( seed: u64 ) -> Self {
+        Self::with_rng( SeededRng::new(seed) )
+    }
+    pub fn with_rng ( rng: impl Rng + Send + 'static ) -> Self {
+        Self::from_backend( Backend::StateVector, rng )
+    }
+    pub fn with_backend ( backend: Backend ) -> Self {
+        Self::from_backend( backend, SeededRng::from_entropy() )
+    }
+    // For reproducible runs on a backend other than the state vector, e.g.
+    // System::with_backend_and_rng( Backend::DensityMatrix, SeededRng::new(7) )
+    pub fn with_backend_and_rng ( backend: Backend, rng: impl Rng + Send + 'static ) -> Self {
+        Self::from_backend( backend, rng )
+    }
+}
+impl<T: Float> System<T> {
+    pub fn dump ( &self ) {
         println!("Machine Dump:");
-        let mut base;
-        match &self.state[0] {
-            StateEntry::StandardQubit(q) => {
-                base = q.state.clone();
-            },
-            StateEntry::EntangledState(s) => {
-                base = s.clone();
+        match self.backend {
+            Backend::StateVector | Backend::SplitComplex => println!("{:?}", self.column()),
+            Backend::DensityMatrix => println!("{:?}", self.density_matrix())
+        }
+    }
+    pub fn dump_register ( &self, index: usize ) -> Result<()> {
+        self.check_index( index )?;
+
+        self.print_register( index );
+        Ok(())
+    }
+    fn print_register ( &self, index: usize ) {
+        println!("Register Dump:");
+
+        if let Some(q) = self.factor_qubit( index ) {
+            q.measure();
+            return;
+        }
+
+        // No state of its own, so describe the reduced density matrix instead
+        let rho = self.reduced_density( &[index] );
+        let purity: f64 = rho.data.iter().map(|entry| entry.norm_sqr().to_f64()).sum();
+        let x = 2f64 * rho[0][1].a.to_f64();
+        let y = -2f64 * rho[0][1].b.to_f64();
+        let z = ( rho[0][0].a - rho[1][1].a ).to_f64();
+
+        match self.backend {
+            Backend::StateVector | Backend::SplitComplex => println!("Register is entangled, with reduced state:"),
+            Backend::DensityMatrix => println!("Register is entangled or mixed, with reduced state:")
+        }
+        println!("{:?}", rho);
+        println!("Chance of being measured as on: {}%", ( rho[1][1].a.to_f64() * 1000000f64 ).round() / 10000f64);
+        println!("Purity: {:.4} | Bloch vector: ({:.4}, {:.4}, {:.4})\n", purity, x, y, z);
+    }
+    // An empty system of any precision on `backend`, drawing measurement
+    // outcomes from `rng`
+    pub fn from_backend ( backend: Backend, rng: impl Rng + Send + 'static ) -> Self {
+        let mut system = System {
+            state: vec![ComplexNumber::one()],
+            split: SplitState::default(),
+            qubits: 0,
+            backend,
+            noise: None,
+            rng: Box::new(rng)
+        };
+        if backend == Backend::SplitComplex {
+            system.split = SplitState::from( &system.state[..] );
+            system.state = Vec::new();
+        }
+        system
+    }
+    pub fn set_rng ( &mut self, rng: impl Rng + Send + 'static ) {
+        self.rng = Box::new(rng);
+    }
+    // From here on, every gate is followed by the channels the model
+    // schedules for it, and measurements are misread as it describes
+    pub fn set_noise_model ( &mut self, model: NoiseModel<T> ) {
+        self.noise = Some(model);
+    }
+    pub fn clear_noise_model ( &mut self ) {
+        self.noise = None;
+    }
+    pub fn noise_model ( &self ) -> Option<&NoiseModel<T>> {
+        self.noise.as_ref()
+    }
+
+    pub fn qubits ( &self ) -> usize {
+        self.qubits
+    }
+    pub fn backend ( &self ) -> Backend {
+        self.backend
+    }
+    // The state as a column vector. A pure density matrix gives back its ket,
+    // up to a global phase; a mixed one has none.
+    pub fn state_vector ( &self ) -> Result<Matrix<T>> {
+        match self.backend {
+            Backend::StateVector | Backend::SplitComplex => Ok(self.column()),
+            Backend::DensityMatrix => {
+                let rho = self.density_matrix();
+
+                let purity: T = rho.data.iter().map(|entry| entry.norm_sqr()).sum();
+                if ( purity - T::one() ).abs() > T::tolerance() {
+                    return Err(QuantumError::MixedState);
+                }
+
+                // Every column of |ψ><ψ| is |ψ> scaled by a conjugated amplitude
+                let pivot = (0..rho.rows)
+                    .max_by(|&x, &y| rho[x][x].a.partial_cmp( &rho[y][y].a ).unwrap_or( std::cmp::Ordering::Equal ))
+                    .expect("There is always at least one basis state");
+                let norm = rho[pivot][pivot].a.sqrt();
+
+                Ok(Matrix::from_rows( (0..rho.rows).map(|r| vec![rho[r][pivot] / norm]).collect() ))
             }
-            StateEntry::EntangledStatePtr(_) => panic!("Impossible")
         }
-        for i in 1..self.state.len() {
-            match &self.state[i] {
-                StateEntry::StandardQubit(q) => {
-                    base = base.tensor_product(&q.state);
-                },
-                StateEntry::EntangledState(s) => {
-                    base = base.tensor_product(&s);
-                },
-                StateEntry::EntangledStatePtr(_) => {}
+    }
+    // ρ, for either backend; a state vector gives |ψ><ψ|
+    pub fn density_matrix ( &self ) -> Matrix<T> {
+        let dimension = 1 << self.qubits;
+        if self.backend == Backend::DensityMatrix {
+            return Matrix { data: self.state.clone(), rows: dimension, cols: dimension };
+        }
+
+        let state = self.amplitudes();
+        let mut ret = Matrix::from_dimensions( dimension, dimension );
+        for r in 0..dimension {
+            for c in 0..dimension {
+                ret[r][c] = state[r] * state[c].conj();
             }
         }
-        println!("{:?}", base);
+        ret
     }
-    pub fn dump_register ( &self, index: usize ) {
-        println!("Register Dump:");
+    fn column ( &self ) -> Matrix<T> {
+        Matrix::from_rows( self.amplitudes().iter().map(|amplitude| vec![*amplitude]).collect() )
+    }
+    // The amplitudes of a pure state, whichever layout holds them
+    fn amplitudes ( &self ) -> Cow<'_, [ComplexNumber<T>]> {
+        match self.backend {
+            Backend::SplitComplex => Cow::Owned(self.split.to_interleaved()),
+            Backend::StateVector | Backend::DensityMatrix => Cow::Borrowed(&self.state)
+        }
+    }
+    // Reduced density matrix of `qubits` with every other register traced
+    // out, qubits[0] being its most significant qubit
+    pub fn reduced_state ( &self, qubits: &[usize] ) -> Result<Matrix<T>> {
+        self.check_distinct( qubits )?;
+
+        Ok(self.reduced_density( qubits ))
+    }
+    // Von Neumann entropy, in bits, of `partition` against the rest of the
+    // system; for a pure state this measures their entanglement
+    pub fn entanglement_entropy ( &self, partition: &[usize] ) -> Result<T> {
+        self.reduced_state( partition )?.von_neumann_entropy()
+    }
+    fn reduced_density ( &self, qubits: &[usize] ) -> Matrix<T> {
+        let n = self.qubits;
+        match self.backend {
+            Backend::StateVector | Backend::SplitComplex => {
+                let state = self.amplitudes();
+                trace_out( n, qubits, |r, c| state[r] * state[c].conj() )
+            },
+            Backend::DensityMatrix => trace_out( n, qubits, |r, c| self.state[( r << n ) | c] )
+        }
+    }
+    // Whether the state matches `expected` (amplitudes in basis order) up to
+    // a global phase
+    pub fn state_matches ( &self, expected: &[ComplexNumber<T>], eps: T ) -> bool {
+        let expected = Matrix::from_rows( expected.iter().map(|amplitude| vec![*amplitude]).collect() );
 
-        if index > self.state.len() {
-            panic!("Register {} does not exist!", index);
+        match self.backend {
+            Backend::StateVector | Backend::SplitComplex => expected.states_equivalent( &self.column(), eps ),
+            // |e><e| carries no global phase to begin with
+            Backend::DensityMatrix => expected.outer_product( &expected )
+                .is_ok_and(|rho| rho.approx_eq( &self.density_matrix(), eps ))
+        }
+    }
+    // Panics unless the state matches `expected` up to a global phase, to
+    // within the default tolerance of the precision
+    pub fn assert_state ( &self, expected: &[ComplexNumber<T>] ) {
+        if !self.state_matches( expected, T::tolerance() ) {
+            let found = match self.backend {
+                Backend::StateVector | Backend::SplitComplex => self.column(),
+                Backend::DensityMatrix => self.density_matrix()
+            };
+            panic!("State does not match the expected state up to a global phase!\nExpected: {:?}\nFound: {:?}", expected, found);
         }
+    }
+    pub fn qubit ( &mut self, index: usize ) -> Result<QubitRef<'_, T>> {
+        self.check_index( index )?;
+
+        Ok(QubitRef { system: self, index })
+    }
+
+    pub fn allocate ( &mut self ) -> QubitRef<'_, T> {
+        self.push_qubit( Qubit::new() )
+    }
+    pub fn allocate_ket ( &mut self, id: &str ) -> Result<QubitRef<'_, T>> {
+        let qubit = Qubit::ket(id)?;
+
+        Ok(self.push_qubit( qubit ))
+    }
+    fn push_qubit ( &mut self, qubit: Qubit<T> ) -> QubitRef<'_, T> {
+        let zero = qubit.state[0][0];
+        let one = qubit.state[1][0];
 
-        match &self.state[index] {
-            StateEntry::StandardQubit(q) => {
-                q.measure();
+        // |state> ⊗ |qubit>, so the new qubit becomes the least significant bit
+        match self.backend {
+            Backend::StateVector => {
+                self.state = self.state
+                    .iter()
+                    .flat_map(|amplitude| [*amplitude * zero, *amplitude * one])
+                    .collect();
             },
-            StateEntry::EntangledState(_) | StateEntry::EntangledStatePtr(_) => {
-                println!("Register is currently entangled!");
+            Backend::SplitComplex => self.split.push_qubit( zero, one ),
+            Backend::DensityMatrix => {
+                // ρ ⊗ |q><q|
+                let ket = [zero, one];
+                let dimension = 1usize << self.qubits;
+
+                let mut state = vec![ComplexNumber::zero(); dimension * dimension * 4];
+                for r in 0..dimension {
+                    for c in 0..dimension {
+                        let entry = self.state[( r << self.qubits ) | c];
+                        for (x, &row_amplitude) in ket.iter().enumerate() {
+                            for (y, &col_amplitude) in ket.iter().enumerate() {
+                                let index = ( ( ( r << 1 ) | x ) << ( self.qubits + 1 ) ) | ( ( c << 1 ) | y );
+                                state[index] = entry * row_amplitude * col_amplitude.conj();
+                            }
+                        }
+                    }
+                }
+                self.state = state;
             }
         }
+        self.qubits += 1;
+
+        let index = self.qubits - 1;
+        QubitRef { system: self, index }
     }
-    pub fn new () -> Self {
-        return System {
-            state: Vec::new()
+
+    /* State Vector Helpers */
+    fn check_index ( &self, index: usize ) -> Result<()> {
+        if index >= self.qubits {
+            return Err(QuantumError::QubitOutOfRange { index, qubits: self.qubits });
         }
+        Ok(())
+    }
+    // Every index must exist and appear at most once
+    fn check_distinct ( &self, indices: &[usize] ) -> Result<()> {
+        for (i, &index) in indices.iter().enumerate() {
+            self.check_index( index )?;
+            if indices[..i].contains( &index ) {
+                return Err(QuantumError::DuplicateQubit( index ));
+            }
+        }
+        Ok(())
+    }
+    fn bit ( &self, index: usize ) -> usize {
+        bit_of( self.qubits, index )
     }
+    // Recovers the single-qubit state of a register if it is not entangled
+    // with the rest of the system, up to a global phase.
+    fn factor_qubit ( &self, index: usize ) -> Option<Qubit<T>> {
+        if self.backend == Backend::DensityMatrix {
+            return self.factor_density_qubit( index );
+        }
+        let bit = self.bit( index );
+        let state = self.amplitudes();
 
-    pub fn allocate ( &mut self ) -> &mut Qubit {
-        self.state.push(StateEntry::StandardQubit(Qubit::new()));
-        match self.state
-            .last_mut()
-            .expect("Since we just allocated an element, it should be impossible to have no element")
-        {
-            StateEntry::StandardQubit(ref mut q) => {
-                return q;
-            },
-            StateEntry::EntangledState(_) | StateEntry::EntangledStatePtr(_) => panic!("Impossible behavior")
+        let mut reference = 0usize;
+        let mut reference_weight = -T::one();
+        for i in (0..state.len()).filter(|i| i & bit == 0) {
+            let weight = state[i].modulus().powi(2) + state[i | bit].modulus().powi(2);
+            if weight > reference_weight {
+                reference = i;
+                reference_weight = weight;
+            }
+        }
+        let zero = state[reference];
+        let one = state[reference | bit];
+
+        // Every other pair of amplitudes must be parallel to the reference
+        // pair, to within the rounding the precision allows for
+        for i in (0..state.len()).filter(|i| i & bit == 0) {
+            let cross = state[i] * one - state[i | bit] * zero;
+            if cross.modulus() > T::tolerance() {
+                return None;
+            }
+        }
+
+        let norm = reference_weight.sqrt();
+        Some(Qubit {
+            state: Matrix::from_rows(vec![
+                vec![zero / norm],
+                vec![one / norm]
+            ])
+        })
+    }
+    // With a density matrix, a register has a state of its own exactly when
+    // its reduced 2x2 density matrix is pure
+    fn factor_density_qubit ( &self, index: usize ) -> Option<Qubit<T>> {
+        let reduced = self.reduced_density( &[index] );
+
+        let purity: T = reduced.data.iter().map(|entry| entry.norm_sqr()).sum();
+        if ( purity - T::one() ).abs() > T::tolerance() {
+            return None;
+        }
+
+        // Either column of |q><q| is |q> up to scale and phase
+        let pivot = if reduced[0][0].a >= reduced[1][1].a { 0 } else { 1 };
+        let norm = reduced[pivot][pivot].a.sqrt();
+        Some(Qubit {
+            state: Matrix::from_rows(vec![
+                vec![reduced[0][pivot] / norm],
+                vec![reduced[1][pivot] / norm]
+            ])
+        })
+    }
+
+    /* Measurement */
+    // Measures a register in the computational basis, collapsing the state.
+    // Returns true if the register was found in |1>.
+    pub fn measure ( &mut self, index: usize ) -> Result<bool> {
+        self.check_index( index )?;
+
+        let mask = self.bit( index );
+        let outcome = self.measure_parity( mask );
+        Ok(self.read_out( index, outcome ))
+    }
+    pub fn measure_many ( &mut self, indices: &[usize] ) -> Result<Vec<bool>> {
+        self.check_distinct( indices )?;
+
+        Ok(indices
+            .iter()
+            .map(|&index| {
+                let outcome = self.measure_parity( self.bit( index ) );
+                self.read_out( index, outcome )
+            })
+            .collect())
+    }
+    pub fn measure_in_basis ( &mut self, basis: Pauli, index: usize ) -> Result<bool> {
+        self.measure_pauli_product( &[(basis, index)] )
+    }
+    // Joint measurement of a Pauli product such as X_0 Z_2, as in Q#'s Measure.
+    // Returns false for the +1 eigenspace and true for the -1 eigenspace.
+    // Under a noise model, the result is reported through the readout errors
+    // of every register with a non-identity factor, as if each had been read
+    // on its own and the bits XORed (see read_out_parity).
+    pub fn measure_pauli_product ( &mut self, paulis: &[(Pauli, usize)] ) -> Result<bool> {
+        let indices: Vec<usize> = paulis.iter().map(|&(_, index)| index).collect();
+        self.check_distinct( &indices )?;
+        let measured: Vec<usize> = paulis
+            .iter()
+            .filter(|&&(basis, _)| basis != Pauli::I)
+            .map(|&(_, index)| index)
+            .collect();
+
+        // Rotate every factor onto Z so the product becomes a parity check
+        let mut mask = 0usize;
+        for &(basis, index) in paulis {
+            if let Some((to_z, _)) = Self::basis_change( basis ) {
+                self.apply_single( &to_z, index );
+            }
+            if basis != Pauli::I {
+                mask |= self.bit( index );
+            }
+        }
+
+        let result = self.measure_parity( mask );
+        let result = self.read_out_parity( &measured, result );
+
+        for &(basis, index) in paulis {
+            if let Some((_, from_z)) = Self::basis_change( basis ) {
+                self.apply_single( &from_z, index );
+            }
+        }
+
+        Ok(result)
+    }
+
+    /* Sampling */
+    // Probability of each computational basis outcome of `indices`, where
+    // indices[0] is the most significant bit of the outcome.
+    pub fn probabilities ( &self, indices: &[usize] ) -> Result<Vec<f64>> {
+        self.check_distinct( indices )?;
+
+        let mut probabilities = vec![0f64; 1 << indices.len()];
+        for i in 0..1usize << self.qubits {
+            let outcome = indices
+                .iter()
+                .fold(0usize, |acc, &index| ( acc << 1 ) | usize::from( i & self.bit( index ) != 0 ));
+
+            probabilities[outcome] += self.basis_probability( i );
         }
+
+        Ok(probabilities)
     }
-    pub fn allocate_ket ( &mut self, id: &str ) -> &mut Qubit {
-        println!("You should complete this process manually!\nQubits start in state |0>.");
-        self.state.push(StateEntry::StandardQubit(Qubit::ket(id)));
+    // Histogram of `shots` terminal measurements of `indices`, keyed by bitstring
+    // (indices[0] first). The distribution is computed once and sampled from,
+    // so the state is neither collapsed nor re-simulated per shot.
+    pub fn sample ( &mut self, shots: usize, indices: &[usize] ) -> Result<BTreeMap<String, usize>> {
+        let cumulative: Vec<f64> = self.misread_probabilities( indices )?
+            .into_iter()
+            .scan(0f64, |total, probability| {
+                *total += probability;
+                Some(*total)
+            })
+            .collect();
+        let total = *cumulative.last().expect("There is always at least one outcome");
+
+        let mut histogram = BTreeMap::new();
+        // Reading no registers, every shot gives the empty bitstring; {:0b}
+        // would print a lone "0" for it
+        if indices.is_empty() {
+            if shots > 0 {
+                histogram.insert( String::new(), shots );
+            }
+            return Ok(histogram);
+        }
+        for _ in 0..shots {
+            let draw = self.rng.next_f64() * total;
+            let outcome = cumulative
+                .partition_point(|&bound| bound <= draw)
+                .min( cumulative.len() - 1 );
+
+            let bitstring = format!("{:0width$b}", outcome, width = indices.len());
+            *histogram.entry( bitstring ).or_insert(0) += 1;
+        }
 
-        match self.state
-            .last_mut()
-            .expect("Since we just allocated an element, it should be impossible to have no element")
-        {
-            StateEntry::StandardQubit(ref mut q) => {
-                return q;
+        Ok(histogram)
+    }
+
+    fn basis_change ( basis: Pauli ) -> Option<(Matrix<T>, Matrix<T>)> {
+        match basis {
+            Pauli::I | Pauli::Z => None,
+            Pauli::X => Some((Gate::H(), Gate::H())),
+            Pauli::Y => {
+                // H S† maps the Y eigenstates onto |0> and |1>
+                Some((Gate::H() * Gate::S_adj(), Gate::S() * Gate::H()))
+            }
+        }
+    }
+    // Outcome distribution of `indices` as reported, after readout errors
+    fn misread_probabilities ( &self, indices: &[usize] ) -> Result<Vec<f64>> {
+        let mut probabilities = self.probabilities( indices )?;
+
+        let Some(model) = &self.noise else { return Ok(probabilities) };
+        for (position, &index) in indices.iter().enumerate() {
+            let Some(matrix) = model.readout( index ) else { continue };
+
+            let bit = 1 << ( indices.len() - 1 - position );
+            let mut misread = vec![0f64; probabilities.len()];
+            for (outcome, probability) in probabilities.iter().enumerate() {
+                let actual = usize::from( outcome & bit != 0 );
+                misread[outcome & !bit] += matrix[0][actual] * probability;
+                misread[outcome | bit] += matrix[1][actual] * probability;
+            }
+            probabilities = misread;
+        }
+
+        Ok(probabilities)
+    }
+    // Reports a parity just measured over `indices` through their readout
+    // errors. A single register is misread exactly as measure() would misread
+    // it. For several, each register's bit is misread independently, so the
+    // chance of reporting odd parity comes from the post-measurement
+    // distribution of those bits, pushed through every readout matrix.
+    fn read_out_parity ( &mut self, indices: &[usize], actual: bool ) -> bool {
+        match *indices {
+            [] => actual,
+            [index] => self.read_out( index, actual ),
+            _ => {
+                let noisy = self.noise
+                    .as_ref()
+                    .is_some_and(|model| indices.iter().any(|&index| model.readout( index ).is_some()));
+                if !noisy {
+                    return actual;
+                }
+
+                let misread = self.misread_probabilities( indices ).expect("Registers were checked before measuring");
+                let total: f64 = misread.iter().sum();
+                let odd: f64 = misread
+                    .iter()
+                    .enumerate()
+                    .filter(|(outcome, _)| outcome.count_ones() % 2 == 1)
+                    .map(|(_, probability)| probability)
+                    .sum();
+                self.rng.next_f64() * total < odd
+            }
+        }
+    }
+    // Reports a measured bit of register `index` through its readout error
+    fn read_out ( &mut self, index: usize, actual: bool ) -> bool {
+        let Some(&matrix) = self.noise.as_ref().and_then(|model| model.readout( index )) else { return actual };
+
+        self.rng.next_f64() < matrix[1][usize::from( actual )]
+    }
+    // Samples the parity of the bits in `mask` by the Born rule, then projects
+    // onto the observed outcome and renormalizes.
+    fn measure_parity ( &mut self, mask: usize ) -> bool {
+        let odd_probability: f64 = (0..1usize << self.qubits)
+            .filter(|i| ( i & mask ).count_ones() % 2 == 1)
+            .map(|i| self.basis_probability( i ))
+            .sum();
+
+        let outcome = self.rng.next_f64() < odd_probability;
+        let kept_probability = if outcome { odd_probability } else { 1f64 - odd_probability };
+
+        match self.backend {
+            Backend::StateVector => {
+                let norm = T::from_f64( kept_probability.sqrt() );
+
+                for (i, amplitude) in self.state.iter_mut().enumerate() {
+                    if ( ( i & mask ).count_ones() % 2 == 1 ) == outcome {
+                        *amplitude /= norm;
+                    } else {
+                        *amplitude = ComplexNumber::zero();
+                    }
+                }
             },
-            StateEntry::EntangledState(_) | StateEntry::EntangledStatePtr(_) => panic!("Impossible behavior")
+            Backend::SplitComplex => self.split.project( mask, outcome, T::from_f64( kept_probability.sqrt() ) ),
+            Backend::DensityMatrix => {
+                // PρP / p, so both the row and the column must carry the outcome
+                let norm = T::from_f64( kept_probability );
+                let columns = ( 1usize << self.qubits ) - 1;
+
+                for (i, entry) in self.state.iter_mut().enumerate() {
+                    let row_kept = ( ( ( i >> self.qubits ) & mask ).count_ones() % 2 == 1 ) == outcome;
+                    let col_kept = ( ( i & columns & mask ).count_ones() % 2 == 1 ) == outcome;
+                    if row_kept && col_kept {
+                        *entry /= norm;
+                    } else {
+                        *entry = ComplexNumber::zero();
+                    }
+                }
+            }
+        }
+
+        outcome
+    }
+    // Born-rule probability of computational basis state i
+    fn basis_probability ( &self, i: usize ) -> f64 {
+        match self.backend {
+            Backend::StateVector => self.state[i].norm_sqr().to_f64(),
+            Backend::SplitComplex => self.split.norm_sqr( i ).to_f64(),
+            Backend::DensityMatrix => self.state[( i << self.qubits ) | i].a.to_f64()
         }
     }
 
     /* Gate Build and Application Logic */
-    pub fn build_gate( &self, inputs: Vec<(usize, Matrix)> ) -> Matrix {
-        let mut current_idx: usize = 0usize;
-        let mut base_matrix = Matrix::new(vec![vec![ComplexNumber { a: 1f32, b: 0f32 }]]);
-        
-        for (idx, matrix) in inputs {
-            while idx > current_idx {
-                base_matrix = base_matrix.tensor_product( &Gate::I() );
-                current_idx += 1;
-            }
+    // Kernel for single-qubit gates on an already validated register
+    fn apply_single ( &mut self, gate: &Matrix<T>, target: usize ) {
+        self.apply_controlled( 0, 0, gate, target );
+    }
+    // A named single-qubit gate, followed by its noise
+    fn apply_gate ( &mut self, name: &str, gate: &Matrix<T>, target: usize ) {
+        self.apply_single( gate, target );
+        self.after_gate( name, &[target] );
+    }
+    // Runs whatever the noise model schedules after `gate` on `qubits`
+    fn after_gate ( &mut self, gate: &str, qubits: &[usize] ) {
+        let Some(model) = self.noise.take() else { return };
 
-            base_matrix = base_matrix.tensor_product( &matrix );
-            current_idx += 1;
+        for (channel, targets) in model.channels_after( gate, qubits ) {
+            self.apply_channel( channel, &targets ).expect("Noise only lands on the registers of a gate that already ran");
         }
+        self.noise = Some(model);
+    }
+    fn apply_controlled ( &mut self, mask: usize, value: usize, gate: &Matrix<T>, target: usize ) {
+        let bit = self.bit( target );
+        match self.backend {
+            Backend::StateVector => apply_controlled_to_amplitudes( &mut self.state, mask, value, gate, bit ),
+            Backend::SplitComplex => self.split.apply_controlled( mask, value, gate, bit ),
+            Backend::DensityMatrix => {
+                // U on the row index, then conj(U) on the column index
+                let n = self.qubits;
+                apply_controlled_to_amplitudes( &mut self.state, mask << n, value << n, gate, bit << n );
+                apply_controlled_to_amplitudes( &mut self.state, mask, value, &gate.conj(), bit );
+            }
+        }
+    }
+    // Applies a single-qubit gate to `target` only where every control is |1>
+    pub fn controlled ( &mut self, controls: &[usize], target: usize, gate: &Matrix<T> ) -> Result<()> {
+        self.controlled_gate( "controlled", controls, target, gate )
+    }
+    // As controlled(), but each control states whether it fires on |1> (true)
+    // or on |0> (false, a negative control)
+    pub fn controlled_on ( &mut self, controls: &[(usize, bool)], target: usize, gate: &Matrix<T> ) -> Result<()> {
+        self.controlled_on_gate( "controlled", controls, target, gate )
+    }
+    fn controlled_gate ( &mut self, name: &str, controls: &[usize], target: usize, gate: &Matrix<T> ) -> Result<()> {
+        let controls: Vec<(usize, bool)> = controls.iter().map(|&index| (index, true)).collect();
+        self.controlled_on_gate( name, &controls, target, gate )
+    }
+    fn controlled_on_gate ( &mut self, name: &str, controls: &[(usize, bool)], target: usize, gate: &Matrix<T> ) -> Result<()> {
+        if gate.rows != 2 || gate.cols != 2 {
+            return Err(QuantumError::DimensionMismatch { expected: (2, 2), found: (gate.rows, gate.cols) });
+        }
+        if !gate.unitary() {
+            return Err(QuantumError::NonUnitaryGate);
+        }
+        let (mask, value) = self.control_mask( controls, &[target] )?;
 
-        while current_idx < self.state.len() {
-            let to_mul: &Matrix = &Gate::I();
+        self.apply_controlled( mask, value, gate, target );
 
-            base_matrix = to_mul.tensor_product( &base_matrix );
+        let mut qubits: Vec<usize> = controls.iter().map(|&(index, _)| index).collect();
+        qubits.push( target );
+        self.after_gate( name, &qubits );
+        Ok(())
+    }
+    // Bit mask selecting the controls, and the value those bits must hold
+    fn control_mask ( &self, controls: &[(usize, bool)], targets: &[usize] ) -> Result<(usize, usize)> {
+        let mut indices: Vec<usize> = targets.to_vec();
+        indices.extend( controls.iter().map(|&(index, _)| index) );
+        self.check_distinct( &indices )?;
 
-            current_idx += 1;
+        let mut mask = 0usize;
+        let mut value = 0usize;
+        for &(index, on_one) in controls {
+            mask |= self.bit( index );
+            if on_one {
+                value |= self.bit( index );
+            }
         }
 
-        base_matrix
+        Ok((mask, value))
     }
+    pub(crate) fn controlled_swap ( &mut self, name: &str, controls: &[usize], register_1_ind: usize, register_2_ind: usize ) -> Result<()> {
+        let controls: Vec<(usize, bool)> = controls.iter().map(|&index| (index, true)).collect();
+        let (mask, value) = self.control_mask( &controls, &[register_1_ind, register_2_ind] )?;
 
-    /* - MULTI-QUBIT GATES - */
-    // SWAP - 'Switch Q_1 and Q_2'
-    #[allow(non_snake_case)]
-    pub fn SWAP ( &mut self, register_1_ind: usize, register_2_ind: usize ) {
-        let qubit_1_state: Matrix = self.state[register_1_ind].unwrap_qubit().state.clone();
-        let qubit_2_state: Matrix = self.state[register_2_ind].unwrap_qubit().state.clone();
+        let bit_1 = self.bit( register_1_ind );
+        let bit_2 = self.bit( register_2_ind );
+        let swap = |state: &mut Vec<ComplexNumber<T>>, mask: usize, value: usize, bit_1: usize, bit_2: usize| {
+            for i in (0..state.len()).filter(|i| i & bit_1 != 0 && i & bit_2 == 0 && i & mask == value) {
+                state.swap( i, i ^ bit_1 ^ bit_2 );
+            }
+        };
+        match self.backend {
+            Backend::StateVector => swap( &mut self.state, mask, value, bit_1, bit_2 ),
+            Backend::SplitComplex => self.split.swap( mask, value, bit_1, bit_2 ),
+            Backend::DensityMatrix => {
+                // A permutation is real, so the column index gets the same swap
+                let n = self.qubits;
+                swap( &mut self.state, mask << n, value << n, bit_1 << n, bit_2 << n );
+                swap( &mut self.state, mask, value, bit_1, bit_2 );
+            }
+        }
 
-        let tensor_product = qubit_1_state.tensor_product(&qubit_2_state);
-        let gate = Matrix::new(
-            vec![
-                vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }],
-            ]
-        );
-        let final_result = gate.clone() * tensor_product.clone();
+        let mut qubits: Vec<usize> = controls.iter().map(|&(index, _)| index).collect();
+        qubits.extend( [register_1_ind, register_2_ind] );
+        self.after_gate( name, &qubits );
+        Ok(())
+    }
+    // Applies a 2^k x 2^k gate to the k registers in `targets`. targets[0] is
+    // the most significant qubit of the gate's basis, so the registers need
+    // not be adjacent or in ascending order.
+    pub fn apply ( &mut self, gate: &Matrix<T>, targets: &[usize] ) -> Result<()> {
+        self.check_targets( gate, targets )?;
 
-        self.state[register_1_ind] = StateEntry::EntangledState(final_result);
-        self.state[register_2_ind] = StateEntry::EntangledStatePtr(register_1_ind);
+        let qubits = self.qubits;
+        match self.backend {
+            Backend::StateVector => apply_to_amplitudes( &mut self.state, qubits, gate, targets ),
+            Backend::SplitComplex => self.split.apply( qubits, gate, targets ),
+            Backend::DensityMatrix => {
+                // ρ is a ket on 2n qubits: rows are registers 0..n, columns n..2n
+                let columns: Vec<usize> = targets.iter().map(|&target| target + qubits).collect();
+                apply_to_amplitudes( &mut self.state, 2 * qubits, gate, targets );
+                apply_to_amplitudes( &mut self.state, 2 * qubits, &gate.conj(), &columns );
+            }
+        }
+
+        self.after_gate( "apply", targets );
+        Ok(())
     }
+    // Applies a noise channel to `targets`. A density matrix evolves exactly
+    // as Σ K ρ K†; a state vector follows one stochastic trajectory, jumping to
+    // K|ψ> / ||K|ψ>|| with probability ||K|ψ>||², which averages to the same ρ.
+    pub fn apply_channel ( &mut self, channel: &Channel<T>, targets: &[usize] ) -> Result<()> {
+        let size = 1 << targets.len();
+        let found = channel.kraus()[0].rows;
+        if found != size {
+            return Err(QuantumError::DimensionMismatch { expected: (size, size), found: (found, found) });
+        }
+        self.check_distinct( targets )?;
 
-    /* - CONTROLLED STANDARD GATES - */
-    // CNOT - 'Swap Q_2 if Q_1' 
-    #[allow(non_snake_case)]
-    pub fn CNOT ( &mut self, register_1_ind: usize, register_2_ind: usize ) {
-        let qubit_1_state: Matrix = self.state[register_1_ind].unwrap_qubit().state.clone();
-        let qubit_2_state: Matrix = self.state[register_2_ind].unwrap_qubit().state.clone();
+        let qubits = self.qubits;
+        match self.backend {
+            Backend::StateVector => {
+                let draw = self.rng.next_f64();
+                let (branch, probability) = trajectory_branch( draw, channel, |operator| {
+                    let mut branch = self.state.clone();
+                    apply_to_amplitudes( &mut branch, qubits, operator, targets );
 
-        let tensor_product = qubit_1_state.tensor_product(&qubit_2_state);
-        let gate = Matrix::new(
-            vec![
-                vec![ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }],
-                vec![ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }, ComplexNumber { a: 1f32, b: 0f32 }, ComplexNumber { a: 0f32, b: 0f32 }],
-            ]
-        );
-        let final_result = gate.clone() * tensor_product.clone();
+                    let probability: f64 = branch.iter().map(|amplitude| amplitude.norm_sqr().to_f64()).sum();
+                    (branch, probability)
+                });
 
-        self.state[register_1_ind] = StateEntry::EntangledState(final_result);
-        self.state[register_2_ind] = StateEntry::EntangledStatePtr(register_1_ind);
+                let norm = T::from_f64( probability.sqrt() );
+                self.state = branch.into_iter().map(|amplitude| amplitude / norm).collect();
+            },
+            Backend::SplitComplex => {
+                let draw = self.rng.next_f64();
+                let (mut branch, probability) = trajectory_branch( draw, channel, |operator| {
+                    let mut branch = self.split.clone();
+                    branch.apply( qubits, operator, targets );
+
+                    let probability: f64 = (0..branch.len()).map(|i| branch.norm_sqr( i ).to_f64()).sum();
+                    (branch, probability)
+                });
+
+                branch.scale( T::from_f64( probability.sqrt() ) );
+                self.split = branch;
+            },
+            Backend::DensityMatrix => {
+                let columns: Vec<usize> = targets.iter().map(|&target| target + qubits).collect();
+
+                let mut state = vec![ComplexNumber::zero(); self.state.len()];
+                for operator in channel.kraus() {
+                    let mut term = self.state.clone();
+                    apply_to_amplitudes( &mut term, 2 * qubits, operator, targets );
+                    apply_to_amplitudes( &mut term, 2 * qubits, &operator.conj(), &columns );
+
+                    for (entry, value) in state.iter_mut().zip( term ) {
+                        *entry += value;
+                    }
+                }
+                self.state = state;
+            }
+        }
+        Ok(())
+    }
+    fn check_targets ( &self, gate: &Matrix<T>, targets: &[usize] ) -> Result<()> {
+        let size = 1 << targets.len();
+        if gate.rows != size || gate.cols != size {
+            return Err(QuantumError::DimensionMismatch { expected: (size, size), found: (gate.rows, gate.cols) });
+        }
+        if !gate.unitary() {
+            return Err(QuantumError::NonUnitaryGate);
+        }
+        self.check_distinct( targets )
     }
-    pub fn CONTROLLED_X ( &mut self, control_idx: usize, target_idx: usize ) -> &Self {
-        let control: Matrix = self[control_idx].unwrap_qubit().state.clone();
-        let target: Matrix = self[target_idx].unwrap_qubit().state.clone();
-        
-        let control_percentage: f32 = control[1][0].a.powi(2) + control[1][0].b.powi(2); 
-        println!("Attempting Controlled X with {:?} ({}%) as control on {:?}", control, control_percentage, target);
+    // Full 2^n x 2^n operator for the given (first register, gate) pairs, where
+    // a 2^k x 2^k gate covers registers first..first + k and every other
+    // register is left alone.
+    pub fn build_gate ( &self, inputs: Vec<(usize, Matrix<T>)> ) -> Result<Matrix<T>> {
+        let mut ret = Matrix::identity( 1 << self.qubits );
 
-        let mut identity_portion: Matrix = Gate::I();
-        for row in 0..identity_portion.value.len() {
-            for col in 0..identity_portion[row].len() {
-                identity_portion.value[row][col] *= (1f32 - control_percentage).sqrt();
+        let mut covered: Vec<usize> = Vec::new();
+        for (first, gate) in inputs {
+            let width = gate.rows.trailing_zeros() as usize;
+            let targets: Vec<usize> = (first..first + width).collect();
+            self.check_targets( &gate, &targets )?;
+            if let Some(&overlap) = targets.iter().find(|target| covered.contains(target)) {
+                return Err(QuantumError::DuplicateQubit( overlap ));
+            }
+            covered.extend( &targets );
+
+            for c in 0..ret.cols {
+                let mut column: Vec<ComplexNumber<T>> = (0..ret.rows).map(|r| ret[r][c]).collect();
+                apply_to_amplitudes( &mut column, self.qubits, &gate, &targets );
+                for (r, amplitude) in column.into_iter().enumerate() {
+                    ret[r][c] = amplitude;
+                }
             }
         }
-        println!("Identity Portion: {:?}", identity_portion);
 
-        let mut gate_portion: Matrix = Gate::X();
-        for row in 0..gate_portion.value.len() {
-            for col in 0..gate_portion[row].len() {
-                gate_portion[row][col] *= control_percentage.sqrt();
+        Ok(ret)
+    }
+    // build_gate() as a sparse operator, composed by sparse tensor products
+    // with identities on the uncovered registers. A single gate on 16 qubits
+    // stores 2^16 * 2^k entries instead of 2^32.
+    pub fn build_sparse_gate ( &self, inputs: Vec<(usize, Matrix<T>)> ) -> Result<SparseMatrix<T>> {
+        let mut placed: Vec<(usize, usize, SparseMatrix<T>)> = Vec::new();
+        let mut covered: Vec<usize> = Vec::new();
+        for (first, gate) in inputs {
+            let width = gate.rows.trailing_zeros() as usize;
+            let targets: Vec<usize> = (first..first + width).collect();
+            self.check_targets( &gate, &targets )?;
+            if let Some(&overlap) = targets.iter().find(|target| covered.contains(target)) {
+                return Err(QuantumError::DuplicateQubit( overlap ));
             }
+            covered.extend( &targets );
+            placed.push( (first, width, SparseMatrix::from( &gate )) );
         }
-        println!("Gate Portion: {:?}", gate_portion);
+        placed.sort_by_key(|&(first, _, _)| first);
 
-        self[target_idx].unwrap_qubit().state = (identity_portion + gate_portion) * self[target_idx].unwrap_qubit().state.clone();
-        self
+        // Qubit 0 is the most significant, so factors go left to right
+        let mut ret = SparseMatrix::identity( 1 );
+        let mut next = 0;
+        for (first, width, gate) in placed {
+            ret = ret.tensor_product( &SparseMatrix::identity( 1 << ( first - next ) ) ).tensor_product( &gate );
+            next = first + width;
+        }
+        Ok(ret.tensor_product( &SparseMatrix::identity( 1 << ( self.qubits - next ) ) ))
+    }
+
+    /* - MULTI-QUBIT GATES - */
+    // SWAP - 'Switch Q_1 and Q_2'
+    #[allow(non_snake_case)]
+    pub fn SWAP ( &mut self, register_1_ind: usize, register_2_ind: usize ) -> Result<()> {
+        self.controlled_swap( "SWAP", &[], register_1_ind, register_2_ind )
+    }
+
+    /* - CONTROLLED STANDARD GATES - */
+    // CNOT - 'Swap Q_2 if Q_1'
+    #[allow(non_snake_case)]
+    pub fn CNOT ( &mut self, register_1_ind: usize, register_2_ind: usize ) -> Result<()> {
+        self.controlled_gate( "CNOT", &[register_1_ind], register_2_ind, &Gate::X() )
+    }
+    // CCNOT - 'Toffoli', flip the target if both controls are on
+    #[allow(non_snake_case)]
+    pub fn CCNOT ( &mut self, control_1_ind: usize, control_2_ind: usize, target_ind: usize ) -> Result<()> {
+        self.controlled_gate( "CCNOT", &[control_1_ind, control_2_ind], target_ind, &Gate::X() )
+    }
+    // CSWAP - 'Fredkin', swap Q_1 and Q_2 if the control is on
+    #[allow(non_snake_case)]
+    pub fn CSWAP ( &mut self, control_ind: usize, register_1_ind: usize, register_2_ind: usize ) -> Result<()> {
+        self.controlled_swap( "CSWAP", &[control_ind], register_1_ind, register_2_ind )
     }
 
     // CR_x - 'Controlled R_x'
     #[allow(non_snake_case)]
-    pub fn CR_x ( &mut self, control: Vec<usize>, args: (usize, f32) ) {
-        
+    pub fn CR_x ( &mut self, controls: &[usize], target: usize, theta: T ) -> Result<()> {
+        self.controlled_gate( "CR_x", controls, target, &Gate::R_x( theta ) )
+    }
+    // CR_y - 'Controlled R_y'
+    #[allow(non_snake_case)]
+    pub fn CR_y ( &mut self, controls: &[usize], target: usize, theta: T ) -> Result<()> {
+        self.controlled_gate( "CR_y", controls, target, &Gate::R_y( theta ) )
+    }
+    // CR_z - 'Controlled R_z'
+    #[allow(non_snake_case)]
+    pub fn CR_z ( &mut self, controls: &[usize], target: usize, theta: T ) -> Result<()> {
+        self.controlled_gate( "CR_z", controls, target, &Gate::R_z( theta ) )
+    }
+    // CR_1 - 'Controlled phase'
+    #[allow(non_snake_case)]
+    pub fn CR_1 ( &mut self, controls: &[usize], target: usize, theta: T ) -> Result<()> {
+        self.controlled_gate( "CR_1", controls, target, &Gate::R_1( theta ) )
     }
 }
+
+fn bit_of ( qubits: usize, index: usize ) -> usize {
+    1 << ( qubits - 1 - index )
+}
+// The branch of a stochastic trajectory that a uniform draw in [0, 1) picks,
+// given each Kraus operator's unnormalized branch and its weight
+fn trajectory_branch<T: Float, B> ( draw: f64, channel: &Channel<T>, branch_of: impl Fn(&Matrix<T>) -> (B, f64) ) -> (B, f64) {
+    let mut cumulative = 0f64;
+    let mut chosen = None;
+    for operator in channel.kraus() {
+        let (branch, probability) = branch_of( operator );
+        if probability == 0f64 {
+            continue;
+        }
+        cumulative += probability;
+        chosen = Some((branch, probability));
+        if draw < cumulative {
+            break;
+        }
+    }
+
+    // Rounding can leave the draw just past the total; the last possible
+    // branch is the right one then
+    chosen.expect("Some Kraus operator has non-zero weight")
+}
@@ -0,0 +1,228 @@
+use crate::{
+    matrix,
+    complex,
+    float
+};
+
+use matrix::Matrix;
+use complex::ComplexNumber;
+use float::Float;
+
+use super::bit_of;
+use super::kernels::target_offsets;
+use super::kernels::parallel_threads;
+
+// A pure state with the real and imaginary parts of its amplitudes in two
+// separate arrays (structure of arrays) rather than interleaved. A
+// single-qubit gate then streams over four contiguous runs of plain floats,
+// re/im of the |0> half and re/im of the |1> half, which the compiler can
+// vectorize.
+#[derive(Clone, Default)]
+pub(crate) struct SplitState<T: Float> {
+    re: Vec<T>,
+    im: Vec<T>
+}
+impl<T: Float> From<&[ComplexNumber<T>]> for SplitState<T> {
+    fn from ( amplitudes: &[ComplexNumber<T>] ) -> Self {
+        Self {
+            re: amplitudes.iter().map(|amplitude| amplitude.a).collect(),
+            im: amplitudes.iter().map(|amplitude| amplitude.b).collect()
+        }
+    }
+}
+impl<T: Float> SplitState<T> {
+    pub(crate) fn to_interleaved ( &self ) -> Vec<ComplexNumber<T>> {
+        self.re.iter().zip( &self.im ).map(|(&a, &b)| ComplexNumber::new( a, b )).collect()
+    }
+    pub(crate) fn len ( &self ) -> usize {
+        self.re.len()
+    }
+    pub(crate) fn get ( &self, i: usize ) -> ComplexNumber<T> {
+        ComplexNumber::new( self.re[i], self.im[i] )
+    }
+    fn set ( &mut self, i: usize, amplitude: ComplexNumber<T> ) {
+        self.re[i] = amplitude.a;
+        self.im[i] = amplitude.b;
+    }
+    pub(crate) fn norm_sqr ( &self, i: usize ) -> T {
+        self.re[i].powi(2) + self.im[i].powi(2)
+    }
+
+    // |state> ⊗ (zero|0> + one|1>)
+    pub(crate) fn push_qubit ( &mut self, zero: ComplexNumber<T>, one: ComplexNumber<T> ) {
+        let mut ret = Self {
+            re: Vec::with_capacity( 2 * self.len() ),
+            im: Vec::with_capacity( 2 * self.len() )
+        };
+        for i in 0..self.len() {
+            for factor in [zero, one] {
+                let amplitude = self.get( i ) * factor;
+                ret.re.push( amplitude.a );
+                ret.im.push( amplitude.b );
+            }
+        }
+        *self = ret;
+    }
+    // Keeps the amplitudes whose parity over `mask` is `outcome`, divided by
+    // norm, and zeroes the rest
+    pub(crate) fn project ( &mut self, mask: usize, outcome: bool, norm: T ) {
+        for (i, (a, b)) in self.re.iter_mut().zip( self.im.iter_mut() ).enumerate() {
+            if ( ( i & mask ).count_ones() % 2 == 1 ) == outcome {
+                *a /= norm;
+                *b /= norm;
+            } else {
+                *a = T::zero();
+                *b = T::zero();
+            }
+        }
+    }
+    pub(crate) fn scale ( &mut self, norm: T ) {
+        for value in self.re.iter_mut().chain( self.im.iter_mut() ) {
+            *value /= norm;
+        }
+    }
+
+    // Applies a single-qubit gate on `bit` wherever the bits in `mask` equal
+    // `value`. The state splits into runs of 2 * bit amplitudes whose first
+    // half has the target bit clear and second half has it set; controls
+    // above the target are fixed along a run, so they are checked once per
+    // run, and a run with no controls below the target is a straight loop.
+    pub(crate) fn apply_controlled ( &mut self, mask: usize, value: usize, gate: &Matrix<T>, bit: usize ) {
+        let threads = parallel_threads( self.len() );
+        self.apply_controlled_on( mask, value, gate, bit, threads );
+    }
+    fn apply_controlled_on ( &mut self, mask: usize, value: usize, gate: &Matrix<T>, bit: usize, threads: Option<usize> ) {
+        let low = mask & ( bit - 1 );
+        let high = mask & !low;
+        let gate = [gate[0][0], gate[0][1], gate[1][0], gate[1][1]];
+
+        for_each_run( &mut self.re, &mut self.im, 2 * bit, threads, |start, re, im| {
+            if start & high != value & high {
+                return;
+            }
+            let (re_zero, re_one) = re.split_at_mut( bit );
+            let (im_zero, im_one) = im.split_at_mut( bit );
+
+            if low == 0 {
+                let pairs = re_zero.iter_mut().zip( im_zero.iter_mut() ).zip( re_one.iter_mut().zip( im_one.iter_mut() ) );
+                for ((zero_a, zero_b), (one_a, one_b)) in pairs {
+                    rotate( &gate, zero_a, zero_b, one_a, one_b );
+                }
+            } else {
+                for j in (0..bit).filter(|j| j & low == value & low) {
+                    rotate( &gate, &mut re_zero[j], &mut im_zero[j], &mut re_one[j], &mut im_one[j] );
+                }
+            }
+        });
+    }
+    // Applies a 2^k x 2^k gate to the registers in `targets`, gathering each
+    // 2^k-amplitude slice into complex numbers and scattering the product back
+    pub(crate) fn apply ( &mut self, qubits: usize, gate: &Matrix<T>, targets: &[usize] ) {
+        if let [target] = targets {
+            self.apply_controlled( 0, 0, gate, bit_of( qubits, *target ) );
+            return;
+        }
+
+        let offsets = target_offsets( qubits, targets );
+        let mask = offsets[offsets.len() - 1];
+
+        let mut gathered: Vec<ComplexNumber<T>> = vec![ComplexNumber::zero(); offsets.len()];
+        for base in (0..self.len()).filter(|i| i & mask == 0) {
+            for (s, &offset) in offsets.iter().enumerate() {
+                gathered[s] = self.get( base + offset );
+            }
+            for (r, &offset) in offsets.iter().enumerate() {
+                let mut amplitude = ComplexNumber::zero();
+                for (c, value) in gathered.iter().enumerate() {
+                    amplitude += gate[r][c] * *value;
+                }
+                self.set( base + offset, amplitude );
+            }
+        }
+    }
+    // Exchanges the amplitudes of bit_1 and bit_2 wherever the bits in
+    // `mask` equal `value`
+    pub(crate) fn swap ( &mut self, mask: usize, value: usize, bit_1: usize, bit_2: usize ) {
+        for i in (0..self.len()).filter(|i| i & bit_1 != 0 && i & bit_2 == 0 && i & mask == value) {
+            self.re.swap( i, i ^ bit_1 ^ bit_2 );
+            self.im.swap( i, i ^ bit_1 ^ bit_2 );
+        }
+    }
+}
+
+// One amplitude pair through a 2x2 gate, given row-major, with the same
+// operations in the same order as the interleaved kernel so that both
+// layouts agree bit for bit
+fn rotate<T: Float> ( gate: &[ComplexNumber<T>; 4], zero_a: &mut T, zero_b: &mut T, one_a: &mut T, one_b: &mut T ) {
+    let (z_a, z_b, o_a, o_b) = (*zero_a, *zero_b, *one_a, *one_b);
+
+    *zero_a = ( gate[0].a * z_a - gate[0].b * z_b ) + ( gate[1].a * o_a - gate[1].b * o_b );
+    *zero_b = ( gate[0].b * z_a + gate[0].a * z_b ) + ( gate[1].b * o_a + gate[1].a * o_b );
+    *one_a = ( gate[2].a * z_a - gate[2].b * z_b ) + ( gate[3].a * o_a - gate[3].b * o_b );
+    *one_b = ( gate[2].b * z_a + gate[2].a * z_b ) + ( gate[3].b * o_a + gate[3].a * o_b );
+}
+// Calls update(start, re, im) on each `run`-long stretch of both arrays,
+// start being the index it begins at. Given threads, each is handed whole
+// runs, so the split is safe without any shared pointers.
+fn for_each_run<T: Float> ( re: &mut [T], im: &mut [T], run: usize, threads: Option<usize>, update: impl Fn(usize, &mut [T], &mut [T]) + Sync ) {
+    match threads {
+        #[cfg(feature = "parallel")]
+        Some(threads) => {
+            let share = re.len().div_ceil( threads ).next_multiple_of( run );
+
+            std::thread::scope(|scope| {
+                for (i, (re, im)) in re.chunks_mut( share ).zip( im.chunks_mut( share ) ).enumerate() {
+                    let update = &update;
+                    scope.spawn(move || {
+                        for (j, (re, im)) in re.chunks_exact_mut( run ).zip( im.chunks_exact_mut( run ) ).enumerate() {
+                            update( i * share + j * run, re, im );
+                        }
+                    });
+                }
+            });
+        },
+        _ => {
+            for (j, (re, im)) in re.chunks_exact_mut( run ).zip( im.chunks_exact_mut( run ) ).enumerate() {
+                update( j * run, re, im );
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use super::*;
+    use crate::random::{
+        Rng,
+        SeededRng
+    };
+    use super::super::kernels::tests::{
+        random_gate,
+        random_state
+    };
+
+    const QUBITS: usize = 15;
+
+    #[test]
+    fn parallel_runs_match_the_serial_path_bit_for_bit () {
+        let mut rng = SeededRng::new( 23 );
+        let initial = SplitState::from( random_state( &mut rng ).as_slice() );
+
+        for threads in [2, 3, 8] {
+            let mut rng = SeededRng::new( threads as u64 );
+            let mut serial = initial.clone();
+            let mut parallel = initial.clone();
+
+            for _ in 0..40 {
+                let target = ( rng.next_u64() % QUBITS as u64 ) as usize;
+                let control = bit_of( QUBITS, ( target + 1 ) % QUBITS );
+                let (mask, value) = if rng.next_u64().is_multiple_of( 2 ) { (0, 0) } else { (control, control) };
+                let gate = random_gate( &mut rng );
+                serial.apply_controlled_on( mask, value, &gate, bit_of( QUBITS, target ), None );
+                parallel.apply_controlled_on( mask, value, &gate, bit_of( QUBITS, target ), Some(threads) );
+            }
+
+            assert!( serial.re == parallel.re && serial.im == parallel.im, "{threads} threads diverged from the serial path" );
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use qsharp_rs::{
+    Circuit,
+    Instruction,
+    Operation,
+    Parameter,
+    QuantumError
+};
+
+fn malformed_reason ( text: &str ) -> String {
+    match Circuit::<f64>::parse( text ) {
+        Err(QuantumError::MalformedCircuit(reason)) => reason,
+        other => panic!("expected a malformed circuit, got {:?}", other)
+    }
+}
+
+/* Parsing */
+#[test]
+fn symbols_named_like_non_finite_floats_round_trip () {
+    let mut circuit = Circuit::new( 1, 0 );
+    circuit.R_x( 0, "inf" ).R_y( 0, "nan" ).R_z( 0, "infinity" ).R_1( 0, "NaN" );
+
+    let parsed: Circuit = circuit.to_string().parse().unwrap();
+    assert_eq!( parsed, circuit );
+    assert_eq!( parsed.symbols().into_iter().collect::<Vec<_>>(), ["NaN", "inf", "infinity", "nan"] );
+}
+#[test]
+fn numeric_angles_round_trip () {
+    let mut circuit = Circuit::new( 1, 0 );
+    circuit.R_x( 0, -0.5 ).R_y( 0, 1e-3 ).R_z( 0, 2.0 );
+
+    let parsed: Circuit = circuit.to_string().parse().unwrap();
+    assert_eq!( parsed, circuit );
+
+    let parsed: Circuit = "R_x(.25) q0\nR_y(+1e2) q0".parse().unwrap();
+    assert_eq!( parsed.instructions()[0], Instruction::Gate { operation: Operation::R_x(Parameter::Value(0.25)), controls: vec![], targets: vec![0] } );
+    assert_eq!( parsed.instructions()[1], Instruction::Gate { operation: Operation::R_y(Parameter::Value(100.0)), controls: vec![], targets: vec![0] } );
+}
+#[test]
+fn non_finite_angles_are_rejected () {
+    for angle in ["-inf", "+nan", "1e999", "-1e999"] {
+        let reason = malformed_reason( &format!( "H q0\nR_x({}) q0", angle ) );
+        assert!( reason.starts_with( "line 2:" ), "{}", reason );
+        assert!( reason.contains( "not finite" ), "{}", reason );
+    }
+    assert!( malformed_reason( "unitary(inf 0, 0 0; 0 0, 1 0) q0" ).contains( "not finite" ) );
+    assert!( malformed_reason( "R_x(1.2.3) q0" ).contains( "not a number" ) );
+}
+#[test]
+fn validation_errors_carry_their_line_number () {
+    // Duplicate register
+    let reason = malformed_reason( "qubits 2\nH q0\n\nctrl(q1) X q1" );
+    assert!( reason.starts_with( "line 4:" ), "{}", reason );
+    assert!( reason.contains( "more than once" ), "{}", reason );
+
+    // Wrong number of targets
+    let reason = malformed_reason( "SWAP q0" );
+    assert_eq!( reason, "line 1: SWAP acts on 2 register(s), but was given 1" );
+
+    // Unitary of the wrong size
+    let reason = malformed_reason( "// comment\nunitary(1 0, 0 0; 0 0, 1 0) q0 q1" );
+    assert!( reason.starts_with( "line 2:" ) && reason.contains( "4x4" ), "{}", reason );
+
+    // Non-unitary matrix
+    let reason = malformed_reason( "unitary(1 0, 1 0; 0 0, 1 0) q0" );
+    assert!( reason.starts_with( "line 1:" ), "{}", reason );
+}
+
+/* Inspection */
+#[test]
+fn gates_on_disjoint_registers_share_a_layer () {
+    let mut circuit = Circuit::new( 4, 0 );
+    circuit.H( 0 ).X( 1 ).T( 2 ).R_y( 3, 0.5 );
+    assert_eq!( circuit.depth(), 1 );
+
+    circuit.CNOT( 0, 1 ).unwrap();
+    circuit.CNOT( 3, 2 ).unwrap();
+    assert_eq!( circuit.depth(), 2 );
+
+    assert_eq!( Circuit::<f64>::new( 2, 0 ).depth(), 0 );
+}
+#[test]
+fn gates_sharing_a_register_run_in_sequence () {
+    let mut circuit = Circuit::<f64>::new( 3, 1 );
+    // q2 idles until the CNOT chain reaches it
+    circuit.H( 0 ).CNOT( 0, 1 ).unwrap();
+    circuit.CNOT( 1, 2 ).unwrap();
+    circuit.H( 0 );
+    assert_eq!( circuit.depth(), 3 );
+
+    // A shared classical bit orders instructions just like a register
+    circuit.measure( 0, 0 );
+    circuit.conditional( 0, true, Instruction::Gate { operation: Operation::X, controls: vec![], targets: vec![2] } ).unwrap();
+    assert_eq!( circuit.depth(), 5 );
+}
+#[test]
+fn gate_counts_use_the_names_the_system_runs () {
+    let mut circuit = Circuit::new( 3, 1 );
+    circuit.H( 0 ).H( 1 ).CNOT( 0, 1 ).unwrap();
+    circuit.CNOT( 1, 2 ).unwrap();
+    circuit.controlled( &[0, 1], &[2], Operation::X ).unwrap();
+    circuit.CR_1( &[2], 0, 0.5 ).unwrap();
+    circuit.measure( 2, 0 );
+
+    let counts: Vec<_> = circuit.gate_counts().into_iter().collect();
+    assert_eq!( counts, [("CCNOT", 1), ("CNOT", 2), ("CR_1", 1), ("H", 2), ("measure", 1)] );
+    assert!( Circuit::<f64>::new( 1, 0 ).gate_counts().is_empty() );
+}
+
+/* Optimization */
+#[test]
+fn optimize_drops_every_gate_that_does_nothing () {
+    let mut circuit = Circuit::new( 2, 0 );
+    circuit.I( 0 ).R_x( 0, 0.0 ).H( 1 ).I( 1 ).R_z( 1, 0.0 ).CR_1( &[0], 1, 0.0 ).unwrap();
+    circuit.controlled( &[1], &[0], Operation::I ).unwrap();
+
+    let optimized = circuit.optimize();
+    assert_eq!( optimized.instructions(), &[Instruction::Gate { operation: Operation::H, controls: vec![], targets: vec![1] }] );
+    assert_eq!( optimized.qubits(), 2 );
+}
+#[test]
+fn optimize_cancels_and_merges_across_dropped_gates () {
+    let mut circuit = Circuit::new( 1, 0 );
+    // The identities in between no longer block the H pair or the rotations
+    circuit.H( 0 ).I( 0 ).H( 0 ).R_y( 0, 0.25 ).I( 0 ).R_y( 0, 0.5 ).R_x( 0, 1.0 ).R_x( 0, -1.0 );
+
+    let optimized = circuit.optimize();
+    assert_eq!( optimized.instructions(), &[Instruction::Gate { operation: Operation::R_y(Parameter::Value(0.75)), controls: vec![], targets: vec![0] }] );
+}
+
+/* Inversion */
+#[test]
+fn inverse_uses_named_adjoint_phase_gates () {
+    let mut circuit = Circuit::new( 2, 0 );
+    circuit.H( 0 ).S( 0 ).T( 1 ).R_x( 1, 0.5 ).S_adj( 1 );
+
+    let inverse = circuit.inverse().unwrap();
+    let names: Vec<_> = inverse.instructions().iter().map(|instruction| instruction.name()).collect();
+    assert_eq!( names, ["S", "R_x", "T_adj", "S_adj", "H"] );
+    assert_eq!( inverse.inverse().unwrap(), circuit );
+
+    // A circuit followed by its inverse optimizes away entirely
+    let mut round_trip = circuit.clone();
+    round_trip.append( &inverse, &[0, 1] ).unwrap();
+    assert!( round_trip.optimize().is_empty() );
+}
+#[test]
+fn inverse_undoes_the_circuit () {
+    let mut circuit = Circuit::new( 2, 0 );
+    circuit.H( 0 ).T( 0 ).S( 1 ).CNOT( 0, 1 ).unwrap();
+    circuit.T_adj( 1 ).R_y( 0, 0.3 );
+
+    let mut system = qsharp_rs::System::seeded( 5 );
+    circuit.run( &mut system ).unwrap();
+    circuit.inverse().unwrap().run( &mut system ).unwrap();
+
+    let probabilities = system.probabilities( &[0, 1] ).unwrap();
+    assert!( ( probabilities[0] - 1. ).abs() < 1e-12 );
+}
+#[test]
+fn noise_on_phase_gates_fires_for_their_adjoints () {
+    use qsharp_rs::{
+        Channel,
+        NoiseModel
+    };
+
+    let mut circuit = Circuit::new( 1, 0 );
+    circuit.H( 0 ).S( 0 );
+    let inverse = circuit.inverse().unwrap();
+
+    // A full phase flip after every S_adj turns H S S_adj H into Z, so
+    // |0> ends in |1>; under the old R_1 form the rule never fired
+    let noise = NoiseModel::new().after_gate( "S_adj", Channel::phase_flip( 1.0 ).unwrap() );
+    let mut system = qsharp_rs::System::seeded( 5 );
+    system.set_noise_model( noise );
+    circuit.run( &mut system ).unwrap();
+    inverse.run( &mut system ).unwrap();
+
+    assert!( ( system.probabilities( &[0] ).unwrap()[1] - 1. ).abs() < 1e-12 );
+}
+
+/* Serialization of the adjoints */
+#[test]
+fn adjoint_gates_round_trip () {
+    let mut circuit = Circuit::new( 1, 0 );
+    circuit.S_adj( 0 ).T_adj( 0 );
+    circuit.controlled( &[1], &[0], Operation::T_adj ).unwrap();
+
+    assert_eq!( circuit.to_string(), "qubits 2\nbits 0\nS_adj q0\nT_adj q0\nctrl(q1) T_adj q0\n" );
+    assert_eq!( circuit.to_string().parse::<Circuit>().unwrap(), circuit );
+}
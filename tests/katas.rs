@@ -0,0 +1,846 @@
+// Each kata is a test here, and a plain function when examples/katas.rs
+// includes this file to run them all as a program
+use qsharp_rs::{
+    ComplexNumber,
+    Matrix,
+    Gate,
+    System,
+    Backend,
+    Channel,
+    NoiseModel,
+    SeededRng,
+    QuantumError
+};
+
+const ONE_OVER_SQRT_TWO: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+// Shorthand for writing out expected amplitudes
+fn c ( a: f64, b: f64 ) -> ComplexNumber {
+    ComplexNumber::new( a, b )
+}
+
+#[cfg_attr(test, test)]
+fn basic_example () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+    q.dump();
+
+    // X Gate
+    q.X();
+    q.dump();
+
+    // Y Gate
+    q.Y();
+    q.dump();
+
+    // Z Gate
+    q.Z();
+
+    system.dump();
+    system.assert_state( &[c(1., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+/* The Y gate */
+#[cfg_attr(test, test)]
+fn exercise_1 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.Y();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 1.)] );
+
+    Ok(())
+}
+/* Applying a global phase i */
+#[cfg_attr(test, test)]
+fn exercise_2 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("I")?;
+
+    q.Z();
+    q.Y();
+    q.X();
+
+    system.dump();
+    // The global phase is the point here, so compare exactly
+    let expected = Matrix::new( vec![vec![c(0., ONE_OVER_SQRT_TWO)], vec![c(-ONE_OVER_SQRT_TWO, 0.)]] )?;
+    assert!( system.state_vector()?.approx_eq( &expected, 1e-10 ) );
+
+    Ok(())
+}
+/* Applying a -1 phase to |0>state */
+#[cfg_attr(test, test)]
+fn exercise_3 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("I")?;
+
+    q.X();
+    q.Z();
+    q.X();
+
+    system.dump();
+    system.assert_state( &[c(-ONE_OVER_SQRT_TWO, 0.), c(0., ONE_OVER_SQRT_TWO)] );
+
+    Ok(())
+}
+/* Preparing a |-> state */
+#[cfg_attr(test, test)]
+fn exercise_4 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.X();
+    q.H();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(-ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+/* Three-fourths phase */
+#[cfg_attr(test, test)]
+fn exercise_5 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("ONE")?;
+
+    q.S();
+    q.T();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(-ONE_OVER_SQRT_TWO, ONE_OVER_SQRT_TWO)] );
+
+    Ok(())
+}
+/* Preparing a rotated state */
+#[cfg_attr(test, test)]
+fn exercise_6 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    let alpha = 0.5f64;
+    let beta = 0.75f64.sqrt();
+
+    // It doesn't matter which is Y or X as
+    // long as the correct trig function is used
+    let parameter_theta = beta.atan2(alpha) * 2f64;
+
+    q.R_y(parameter_theta);
+
+    system.dump();
+    system.assert_state( &[c(0.5, 0.), c(0.75f64.sqrt(), 0.)] );
+
+    Ok(())
+}
+/* Preparing an arbitrary state */
+#[cfg_attr(test, test)]
+fn exercise_7 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    let alpha = 0.5f64;
+    let beta = 0.75f64.sqrt();
+    let theta = std::f64::consts::FRAC_PI_2;
+
+    // It doesn't matter which is Y or X as
+    // long as the correct trig function is used
+    let parameter_theta = beta.atan2(alpha) * 2f64;
+
+    q.R_y(parameter_theta);
+    q.dump();
+
+    q.R_1(theta);
+
+    system.dump();
+    system.assert_state( &[c(0.5, 0.), c(0., 0.75f64.sqrt())] );
+
+    Ok(())
+}
+
+/* QUANTUM KATAS */
+/* Task 1.1. State flip: |0> to |1> and vice versa */
+#[cfg_attr(test, test)]
+fn task1_1 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.X();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(1., 0.)] );
+
+    Ok(())
+}
+/* Task 1.2. Basis change: |0> to |+> and |1> to |-> (and vice versa) */
+#[cfg_attr(test, test)]
+fn task1_2 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.H();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+/* Task 1.3. Sign flip: |+> to |-> and vice versa. */
+#[cfg_attr(test, test)]
+fn task1_3 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("PLUS")?;
+
+    q.Z();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(-ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+/* Task 1.4. Amplitude change: |0> to |1>. */
+#[cfg_attr(test, test)]
+fn task1_4 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+    let theta = std::f64::consts::FRAC_PI_2;
+
+    q.R_y(theta * 2f64);
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(1., 0.)] );
+
+    Ok(())
+}
+/* Task 1.5. Phase flip */
+#[cfg_attr(test, test)]
+fn task1_5 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("ONE")?;
+
+    q.S();
+
+    // Alternatively, using the R1 gate suffices:
+    // q.R_1(std::consts::f64::FRAC_PI_2)
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 1.)] );
+
+    Ok(())
+}
+/* Task 1.6. Phase Change */
+#[cfg_attr(test, test)]
+fn task1_6 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+    let theta = std::f64::consts::FRAC_PI_2;
+
+    q.R_1( theta );
+
+    system.dump();
+    system.assert_state( &[c(1., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+/* Task 1.7. Global phase change */
+#[cfg_attr(test, test)]
+fn task1_7 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("PLUS")?;
+
+    q.Z();
+    q.X();
+    q.Z();
+    q.X();
+
+    system.dump();
+    // The global phase is the point here, so compare exactly
+    let expected = Matrix::new( vec![vec![c(-ONE_OVER_SQRT_TWO, 0.)], vec![c(-ONE_OVER_SQRT_TWO, 0.)]] )?;
+    assert!( system.state_vector()?.approx_eq( &expected, 1e-10 ) );
+
+    Ok(())
+}
+
+/* BASIC GATES EXERCISES */
+/* Task 1.1. State flip: |0> to |1> and vice versa */
+#[cfg_attr(test, test)]
+fn bg_task1_1 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.X();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(1., 0.)] );
+
+    Ok(())
+}
+
+/* Task 1.2. Basis change: |0> to |+> and |1> to |-> (and vice versa) */
+#[cfg_attr(test, test)]
+fn bg_task1_2 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+
+    q.H();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+
+/* Task 1.3. Basis change: |0> to |+> and |1> to |-> (and vice versa) */
+#[cfg_attr(test, test)]
+fn bg_task1_3 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("PLUS")?;
+
+    q.Z();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(-ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+
+/* Task 1.4. Amplitude change: |0> to cos(alpha)|0> + sin(alpha)|1>. */
+#[cfg_attr(test, test)]
+fn bg_task1_4 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate();
+    let alpha = 32.334f64;
+
+    q.R_y(alpha * 2f64);
+
+    system.dump();
+    system.assert_state( &[c(alpha.cos(), 0.), c(alpha.sin(), 0.)] );
+
+    Ok(())
+}
+
+/* Task 1.5. Phase Flip */
+#[cfg_attr(test, test)]
+fn bg_task1_5 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("ONE")?;
+
+    q.S();
+
+    // or,
+    // q.T();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 1.)] );
+
+    Ok(())
+}
+
+/* Task 1.6. Phase change */
+#[cfg_attr(test, test)]
+fn bg_task1_6 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("ONE")?;
+    let alpha = std::f64::consts::FRAC_PI_2;
+
+    q.R_1( alpha );
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 1.)] );
+
+    Ok(())
+}
+
+/* Task 1.7. Global phase change */
+#[cfg_attr(test, test)]
+fn bg_task1_7 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    let mut q = system.allocate_ket("PLUS")?;
+
+    q.Z();
+    q.X();
+    q.Z();
+    q.X();
+
+    system.dump();
+    // The global phase is the point here, so compare exactly
+    let expected = Matrix::new( vec![vec![c(-ONE_OVER_SQRT_TWO, 0.)], vec![c(-ONE_OVER_SQRT_TWO, 0.)]] )?;
+    assert!( system.state_vector()?.approx_eq( &expected, 1e-10 ) );
+
+    Ok(())
+}
+
+/* Task 1.8. Bell state change -1 */
+#[cfg_attr(test, test)]
+fn bg_task1_8 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    // Prepare |Φ+> first, then flip the phase of |11>
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    system.qubit(0)?.Z();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(0., 0.), c(0., 0.), c(-ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+
+/* MULTI QUBIT SYSTEMS EXERCISES */
+/* Exercise 1: Show that the state is separable */
+// Yes, (1/sqrt(2))[ 1, -i ] * (1/sqrt(2))[1, i]
+
+/* Exercise 2: Is this separable? */
+// No, the system of equations has no solution
+
+/* Exercise 3: Prepare a basis state */
+#[cfg_attr(test, test)]
+fn mqs_exercise_3 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate_ket("ONE")?;
+    system.allocate();
+
+    system.qubit(0)?.X();
+    system.qubit(1)?.X();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(1., 0.), c(0., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+
+/* Exercise 4: Prepare a superposition of two basis states */
+#[cfg_attr(test, test)]
+fn mqs_exercise_4 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(1)?.X();
+    system.qubit(1)?.H();
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(-ONE_OVER_SQRT_TWO, 0.), c(0., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+
+/* Exercise 5: Prepare a superposition with real amplitudes */
+#[cfg_attr(test, test)]
+fn mqs_exercise_5 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+
+    system.qubit(1)?.X();
+    system.qubit(1)?.H();
+
+    system.dump();
+    system.assert_state( &[c(0.5, 0.), c(-0.5, 0.), c(0.5, 0.), c(-0.5, 0.)] );
+
+    Ok(())
+}
+
+/* Exercise 6: Prepare a superposition with complex amplitudes */
+#[cfg_attr(test, test)]
+fn mqs_exercise_6 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+    system.qubit(0)?.R_1(std::f64::consts::FRAC_PI_2);
+
+    system.qubit(1)?.H();
+    system.qubit(1)?.R_1(std::f64::consts::FRAC_PI_4);
+
+    system.dump();
+    system.assert_state( &[c(0.5, 0.), c(0.5 * ONE_OVER_SQRT_TWO, 0.5 * ONE_OVER_SQRT_TWO), c(0., 0.5), c(-0.5 * ONE_OVER_SQRT_TWO, 0.5 * ONE_OVER_SQRT_TWO)] );
+
+    Ok(())
+}
+
+/* MULTI QUBIT GATES EXERCISES */
+/* Exercise 1: Compound Gate */
+#[cfg_attr(test, test)]
+fn mqg_exercise_1 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.S();
+    system.qubit(1)?.I();
+    system.qubit(2)?.Y();
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 1.), c(0., 0.), c(0., 0.), c(0., 0.), c(0., 0.), c(0., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+/* Exercise 2: Preparing a Bell state */
+#[cfg_attr(test, test)]
+fn mqg_exercise_2 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(0., 0.), c(0., 0.), c(ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+/* Exercise 3: Swapping two qubits */
+#[cfg_attr(test, test)]
+fn mqg_exercise_3 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.X();
+
+    system.dump_register(0)?;
+    system.dump_register(1)?;
+
+    system.SWAP(0, 1)?;
+
+    system.dump_register(0)?;
+    system.dump_register(1)?;
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(1., 0.), c(0., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+/* Exercise 4: Controlled Rotation */
+#[cfg_attr(test, test)]
+fn mqg_exercise_4 () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.X();
+    system.qubit(1)?.X();
+
+    system.CR_x( &[0], 1, std::f64::consts::FRAC_PI_2 )?;
+
+    system.dump();
+    system.assert_state( &[c(0., 0.), c(0., 0.), c(0., 0.), c(0., 0.), c(0., -ONE_OVER_SQRT_TWO), c(0., 0.), c(ONE_OVER_SQRT_TWO, 0.), c(0., 0.)] );
+
+    Ok(())
+}
+
+/* Other */
+#[cfg_attr(test, test)]
+fn inverse_tensor_product_test () -> Result<(), QuantumError> {
+    let a = Matrix::new(vec![
+        vec![ComplexNumber{ a: 0f64, b: 0f64 }],
+        vec![ComplexNumber{ a: 0f64, b: 1f64 }],
+        vec![ComplexNumber{ a: 0f64, b: 0f64 }]
+    ])?;
+    let b = Matrix::new(vec![
+        vec![ComplexNumber{ a: 1f64, b: 0f64 }],
+        vec![ComplexNumber{ a: 0f64, b: 0f64 }]
+    ])?;
+    let product = a.tensor_product( &b );
+
+    // Dividing A back out of A ⊗ B leaves B
+    let assumed_b = product.inverse_tensor_product( a.clone() )?;
+    println!("Assumed B: {:?}", assumed_b);
+    assert_eq!( assumed_b, b );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn controlled_gate_test () -> Result<(), QuantumError> {
+    let mut system = System::new();
+
+    system.allocate();
+    system.qubit(0)?.X();
+    system.qubit(0)?.T();
+    system.qubit(0)?.H();
+    system.allocate();
+
+    println!("Before dump:");
+    system.dump_register(0)?;
+    system.dump_register(1)?;
+
+    // The control is in superposition, so this entangles the pair
+    system.controlled( &[0], 1, &Gate::X() )?;
+    println!("After dump:");
+    system.dump_register(0)?;
+    system.dump_register(1)?;
+
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(0., 0.), c(0., 0.), c(-ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn measurement_test () -> Result<(), QuantumError> {
+    let mut system = System::seeded(7);
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    let first = system.measure(0)?;
+    let second = system.measure(1)?;
+    println!("Measured Bell pair as ({}, {})", first, second);
+
+    system.dump();
+
+    // Both halves of a Bell pair always agree, and the state collapses onto them
+    assert_eq!( first, second );
+    let mut expected = vec![c(0., 0.); 4];
+    expected[ usize::from( first ) << 1 | usize::from( second ) ] = c(1., 0.);
+    system.assert_state( &expected );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn sampling_test () -> Result<(), QuantumError> {
+    let mut system = System::seeded(7);
+
+    system.allocate();
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+    system.qubit(2)?.R_y(std::f64::consts::FRAC_PI_3);
+
+    let histogram = system.sample( 1000, &[0, 1, 2] )?;
+    println!("Bell pair plus a rotated qubit, 1000 shots: {:?}", histogram);
+    assert_eq!( histogram.values().sum::<usize>(), 1000 );
+
+    // The Bell pair always agrees, and R_y(π/3) gives P(1) = sin²(π/6) = 1/4
+    // on the third qubit; Binomial(1000, 1/4) has a standard deviation of
+    // about 13.7, so this is a five-sigma bound
+    assert!( histogram.keys().all(|key| key[0..1] == key[1..2]) );
+    let ones: usize = histogram.iter().filter(|(key, _)| key.ends_with('1')).map(|(_, count)| count).sum();
+    assert!( ( 250 - 69..=250 + 69 ).contains( &ones ), "{} ones", ones );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn density_matrix_test () -> Result<(), QuantumError> {
+    let mut system = System::with_backend( Backend::DensityMatrix );
+
+    system.allocate();
+    system.allocate();
+
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    // Same Bell pair as MQG Exercise 2, now held as ρ = |Φ+><Φ+|
+    system.dump();
+    system.assert_state( &[c(ONE_OVER_SQRT_TWO, 0.), c(0., 0.), c(0., 0.), c(ONE_OVER_SQRT_TWO, 0.)] );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn noise_test () -> Result<(), QuantumError> {
+    let bit_flip = Channel::bit_flip( 0.25 )?;
+
+    // Exactly, on a density matrix: ρ = 0.75 |0><0| + 0.25 |1><1|
+    let mut system = System::with_backend( Backend::DensityMatrix );
+    system.allocate();
+    system.apply_channel( &bit_flip, &[0] )?;
+    system.dump();
+    let probabilities = system.probabilities( &[0] )?;
+    println!("Probabilities after a 25% bit flip: {:?}", probabilities);
+    assert!( ( probabilities[0] - 0.75 ).abs() < 1e-12 && ( probabilities[1] - 0.25 ).abs() < 1e-12 );
+
+    // As stochastic trajectories on a state vector, where each run either
+    // flips or doesn't, so only the statistics match
+    let mut flips = 0;
+    for seed in 0..1000 {
+        let mut system = System::seeded( seed );
+        system.allocate();
+        system.apply_channel( &bit_flip, &[0] )?;
+        flips += usize::from( system.measure(0)? );
+    }
+    println!("Flipped in {} of 1000 trajectories", flips);
+
+    // Binomial(1000, 0.25) has a standard deviation of about 13.7, so this
+    // is a five-sigma bound
+    assert!( ( 250 - 69..=250 + 69 ).contains( &flips ) );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn noise_model_test () -> Result<(), QuantumError> {
+    let mut system = System::with_backend_and_rng( Backend::DensityMatrix, SeededRng::new(7) );
+    system.set_noise_model(
+        NoiseModel::new()
+            .after_gate( "H", Channel::depolarizing( 0.01 )? )
+            .after_two_qubit_gates( Channel::depolarizing( 0.05 )? )
+            .readout_flip( 0, 0.02 )?
+            .readout_flip( 1, 0.02 )?
+    );
+
+    system.allocate();
+    system.allocate();
+
+    // The same Bell pair circuit, with noise inserted after every gate
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    let histogram = system.sample( 1000, &[0, 1] )?;
+    println!("Noisy Bell pair, 1000 shots: {:?}", histogram);
+    assert_eq!( histogram.values().sum::<usize>(), 1000 );
+    // The noise lets "01" and "10" through, but they stay rare
+    let mismatched = histogram.get( "01" ).unwrap_or( &0 ) + histogram.get( "10" ).unwrap_or( &0 );
+    assert!( mismatched < 150 );
+
+    // With an empty model the pair always agrees
+    let mut system = System::with_backend_and_rng( Backend::DensityMatrix, SeededRng::new(7) );
+    system.set_noise_model( NoiseModel::new() );
+    system.allocate();
+    system.allocate();
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+    let histogram = system.sample( 1000, &[0, 1] )?;
+    assert_eq!( histogram.values().sum::<usize>(), 1000 );
+    assert!( histogram.keys().all(|key| key == "00" || key == "11") );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn noise_model_channels_test () -> Result<(), QuantumError> {
+    // Depolarizing after H only: |+><+| keeps its populations, and its
+    // coherence shrinks to (1 - 4p/3) / 2 since Y and Z both map |+> to |->
+    let p = 0.3;
+    let mut system = System::with_backend( Backend::DensityMatrix );
+    system.set_noise_model( NoiseModel::new().after_gate( "H", Channel::depolarizing( p )? ) );
+    system.allocate();
+    system.qubit(0)?.H();
+
+    let rho = system.density_matrix();
+    assert!( ( rho[0][0].a - 0.5 ).abs() < 1e-12 && ( rho[1][1].a - 0.5 ).abs() < 1e-12 );
+    assert!( ( rho[0][1].a - 0.5 * ( 1. - 4. * p / 3. ) ).abs() < 1e-12 );
+
+    // A phase flip after every two-qubit gate hits both of its registers,
+    // scaling the Bell pair's coherence by (1 - 2q)^2; H is left alone
+    let q = 0.1;
+    let mut system = System::with_backend( Backend::DensityMatrix );
+    system.set_noise_model( NoiseModel::new().after_two_qubit_gates( Channel::phase_flip( q )? ) );
+    system.allocate();
+    system.allocate();
+    system.qubit(0)?.H();
+    system.CNOT(0, 1)?;
+
+    let rho = system.density_matrix();
+    assert!( ( rho[0][0].a - 0.5 ).abs() < 1e-12 && ( rho[3][3].a - 0.5 ).abs() < 1e-12 );
+    assert!( ( rho[0][3].a - 0.5 * ( 1. - 2. * q ).powi( 2 ) ).abs() < 1e-12 );
+    assert!( rho[1][1].a.abs() < 1e-12 && rho[2][2].a.abs() < 1e-12 );
+
+    Ok(())
+}
+#[cfg_attr(test, test)]
+fn readout_noise_test () -> Result<(), QuantumError> {
+    // |0> stays |0>; only its readings flip, 10% of the time
+    let mut system = System::seeded(7);
+    system.set_noise_model( NoiseModel::new().readout_flip( 0, 0.1 )? );
+    system.allocate();
+
+    let histogram = system.sample( 1000, &[0] )?;
+    assert_eq!( histogram.values().sum::<usize>(), 1000 );
+    // Binomial(1000, 0.1) has a standard deviation of about 9.5
+    let flips = *histogram.get( "1" ).unwrap_or( &0 );
+    assert!( ( 100 - 48..=100 + 48 ).contains( &flips ), "{} flips", flips );
+    system.assert_state( &[c(1., 0.), c(0., 0.)] );
+
+    Ok(())
+}
+
+// Every kata in order, for examples/katas.rs
+#[cfg(not(test))]
+pub type Kata = fn() -> Result<(), QuantumError>;
+#[cfg(not(test))]
+pub const KATAS: &[(&str, Kata)] = &[
+    ("basic_example", basic_example),
+    ("exercise_1", exercise_1),
+    ("exercise_2", exercise_2),
+    ("exercise_3", exercise_3),
+    ("exercise_4", exercise_4),
+    ("exercise_5", exercise_5),
+    ("exercise_6", exercise_6),
+    ("exercise_7", exercise_7),
+    ("task1_1", task1_1),
+    ("task1_2", task1_2),
+    ("task1_3", task1_3),
+    ("task1_4", task1_4),
+    ("task1_5", task1_5),
+    ("task1_6", task1_6),
+    ("task1_7", task1_7),
+    ("bg_task1_1", bg_task1_1),
+    ("bg_task1_2", bg_task1_2),
+    ("bg_task1_3", bg_task1_3),
+    ("bg_task1_4", bg_task1_4),
+    ("bg_task1_5", bg_task1_5),
+    ("bg_task1_6", bg_task1_6),
+    ("bg_task1_7", bg_task1_7),
+    ("bg_task1_8", bg_task1_8),
+    ("mqs_exercise_3", mqs_exercise_3),
+    ("mqs_exercise_4", mqs_exercise_4),
+    ("mqs_exercise_5", mqs_exercise_5),
+    ("mqs_exercise_6", mqs_exercise_6),
+    ("mqg_exercise_1", mqg_exercise_1),
+    ("mqg_exercise_2", mqg_exercise_2),
+    ("mqg_exercise_3", mqg_exercise_3),
+    ("mqg_exercise_4", mqg_exercise_4),
+    ("inverse_tensor_product_test", inverse_tensor_product_test),
+    ("controlled_gate_test", controlled_gate_test),
+    ("measurement_test", measurement_test),
+    ("sampling_test", sampling_test),
+    ("density_matrix_test", density_matrix_test),
+    ("noise_test", noise_test),
+    ("noise_model_test", noise_model_test),
+    ("noise_model_channels_test", noise_model_channels_test),
+    ("readout_noise_test", readout_noise_test)
+];
@@ -0,0 +1,433 @@
+use qsharp_rs::{
+    ComplexNumber,
+    Matrix,
+    QuantumError
+};
+
+fn c ( a: f64, b: f64 ) -> ComplexNumber {
+    ComplexNumber::new( a, b )
+}
+fn real ( rows: &[&[f64]] ) -> Matrix {
+    Matrix::new( rows.iter().map(|row| row.iter().map(|&x| c( x, 0. )).collect()).collect() ).unwrap()
+}
+// A dense, well-conditioned complex matrix with no special structure
+fn general () -> Matrix {
+    Matrix::new(vec![
+        vec![c( 4., 1. ), c( -1., 2. ), c( 0.5, 0. )],
+        vec![c( 2., -3. ), c( 3., 0.5 ), c( 1., 1. )],
+        vec![c( 0., 1. ), c( -2., -1. ), c( 5., -2. )]
+    ]).unwrap()
+}
+fn bell () -> Matrix {
+    let s = std::f64::consts::FRAC_1_SQRT_2;
+    real( &[&[s], &[0.], &[0.], &[s]] )
+}
+
+/* Construction */
+#[test]
+fn new_rejects_matrices_without_entries () {
+    assert_eq!( Matrix::<f64>::new( vec![] ), Err(QuantumError::MalformedMatrix) );
+    assert_eq!( Matrix::<f64>::new( vec![vec![]] ), Err(QuantumError::MalformedMatrix) );
+    assert_eq!( Matrix::<f64>::new( vec![vec![], vec![]] ), Err(QuantumError::MalformedMatrix) );
+    assert_eq!( Matrix::new( vec![vec![c( 1., 0. )], vec![]] ), Err(QuantumError::MalformedMatrix) );
+
+    let column = Matrix::new( vec![vec![c( 1., 0. )], vec![c( 0., 1. )]] ).unwrap();
+    assert_eq!( (column.rows, column.cols), (2, 1) );
+}
+
+/* Entanglement measures */
+#[test]
+fn bell_state_is_maximally_entangled () {
+    assert!( ( bell().concurrence().unwrap() - 1. ).abs() < 1e-9 );
+    assert!( ( bell().negativity().unwrap() - 0.5 ).abs() < 1e-9 );
+
+    let mixed = real( &[&[0.5, 0.], &[0., 0.5]] );
+    assert!( ( mixed.von_neumann_entropy().unwrap() - 1. ).abs() < 1e-9 );
+    assert!( ( mixed.renyi_entropy( 2. ).unwrap() - 1. ).abs() < 1e-9 );
+}
+#[test]
+fn schmidt_coefficients_count_the_entanglement () {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    let terms = bell().schmidt_decomposition( 2 ).unwrap();
+    let coefficients: Vec<f64> = terms.iter().map(|(s, _, _)| *s).collect();
+    assert_eq!( coefficients.len(), 2 );
+    assert!( coefficients.iter().all(|s| ( s - FRAC_1_SQRT_2 ).abs() < 1e-9) );
+    // The terms add back up to the state
+    let sum = terms.iter().fold(Matrix::from_dimensions( 4, 1 ), |sum, (s, a, b)| sum + a.tensor_product( b ) * c( *s, 0. ));
+    assert!( sum.approx_eq( &bell(), 1e-9 ) );
+
+    // |+> ⊗ |1> is a product state, with a single term
+    let plus = real( &[&[FRAC_1_SQRT_2], &[FRAC_1_SQRT_2]] );
+    let product = plus.tensor_product( &real( &[&[0.], &[1.]] ) );
+    let terms = product.schmidt_decomposition( 2 ).unwrap();
+    assert_eq!( terms.len(), 1 );
+    assert!( ( terms[0].0 - 1. ).abs() < 1e-9 );
+    assert!( terms[0].1.tensor_product( &terms[0].2 ).approx_eq( &product, 1e-9 ) );
+}
+#[test]
+fn entanglement_measures_reject_non_states () {
+    let non_hermitian = Matrix::new(vec![
+        vec![c( 0.5, 0. ), c( 0.5, 0. )],
+        vec![c( 0., 0. ), c( 0.5, 0. )]
+    ]).unwrap();
+    assert_eq!( non_hermitian.von_neumann_entropy(), Err(QuantumError::NonHermitianMatrix) );
+    assert_eq!( non_hermitian.renyi_entropy( 2. ), Err(QuantumError::NonHermitianMatrix) );
+
+    let unnormalized = real( &[&[1., 0.], &[0., 1.]] );
+    assert_eq!( unnormalized.von_neumann_entropy(), Err(QuantumError::UnnormalizedState( 2. )) );
+
+    let unnormalized_ket = real( &[&[1.], &[0.], &[0.], &[1.]] );
+    assert_eq!( unnormalized_ket.concurrence(), Err(QuantumError::UnnormalizedState( 2. )) );
+
+    let mut non_hermitian_pair = Matrix::new( vec![vec![c( 0.25, 0. ); 4]; 4] ).unwrap();
+    non_hermitian_pair[0][1] = c( 0., 0.25 );
+    assert_eq!( non_hermitian_pair.negativity(), Err(QuantumError::NonHermitianMatrix) );
+    assert_eq!( non_hermitian_pair.concurrence(), Err(QuantumError::NonHermitianMatrix) );
+}
+
+/* State and gate distances */
+#[test]
+fn distances_between_orthogonal_states () {
+    let zero = real( &[&[1.], &[0.]] );
+    let one = real( &[&[0.], &[1.]] );
+    let mixed = real( &[&[0.5, 0.], &[0., 0.5]] );
+
+    assert!( ( zero.fidelity( &zero ).unwrap() - 1. ).abs() < 1e-9 );
+    assert!( zero.fidelity( &one ).unwrap().abs() < 1e-9 );
+    assert!( ( zero.fidelity( &mixed ).unwrap() - 0.5 ).abs() < 1e-9 );
+    assert!( ( zero.trace_distance( &one ).unwrap() - 1. ).abs() < 1e-9 );
+    assert!( ( zero.trace_distance( &mixed ).unwrap() - 0.5 ).abs() < 1e-9 );
+}
+#[test]
+fn distances_between_gates () {
+    use qsharp_rs::Gate;
+
+    let (x, z): (Matrix, Matrix) = (Gate::X(), Gate::Z());
+    // X†Z has eigenvalues ±i, half a turn apart, so one input tells them apart perfectly
+    assert!( ( x.diamond_distance( &z ).unwrap() - 2. ).abs() < 1e-9 );
+    assert!( ( x.average_gate_fidelity( &z ).unwrap() - 1. / 3. ).abs() < 1e-9 );
+
+    assert!( x.diamond_distance( &x ).unwrap().abs() < 1e-9 );
+    assert!( ( x.average_gate_fidelity( &x ).unwrap() - 1. ).abs() < 1e-9 );
+    // A global phase is invisible to both
+    let phased = x.clone() * c( 0., 1. );
+    assert!( x.diamond_distance( &phased ).unwrap().abs() < 1e-9 );
+    assert!( ( x.average_gate_fidelity( &phased ).unwrap() - 1. ).abs() < 1e-9 );
+
+    // R_z(θ) against I: eigenvalues e^{±iθ/2} span an arc of θ
+    let theta: f64 = 0.3;
+    let expected = 2. * ( theta / 2. ).sin();
+    assert!( ( Gate::R_z( theta ).diamond_distance( &Gate::I() ).unwrap() - expected ).abs() < 1e-9 );
+}
+#[test]
+fn distances_reject_non_states () {
+    let zero = real( &[&[1.], &[0.]] );
+    let non_hermitian = Matrix::new(vec![
+        vec![c( 0.5, 0. ), c( 0.5, 0. )],
+        vec![c( 0., 0. ), c( 0.5, 0. )]
+    ]).unwrap();
+    let unnormalized = real( &[&[1., 0.], &[0., 1.]] );
+
+    assert_eq!( zero.fidelity( &non_hermitian ), Err(QuantumError::NonHermitianMatrix) );
+    assert_eq!( non_hermitian.trace_distance( &zero ), Err(QuantumError::NonHermitianMatrix) );
+    assert_eq!( unnormalized.fidelity( &unnormalized ), Err(QuantumError::UnnormalizedState( 2. )) );
+    assert_eq!( zero.trace_distance( &real( &[&[1.], &[1.]] ) ), Err(QuantumError::UnnormalizedState( 2. )) );
+}
+
+/* LU: determinant, inverse and solve */
+#[test]
+fn matrix_times_inverse_is_identity () {
+    let a = general();
+    let mut inverse = a.clone();
+    inverse.invert().unwrap();
+
+    assert!( ( &a * &inverse ).approx_eq( &Matrix::identity( 3 ), 1e-12 ) );
+    assert!( ( &inverse * &a ).approx_eq( &Matrix::identity( 3 ), 1e-12 ) );
+}
+#[test]
+fn solve_matches_the_right_hand_side () {
+    let a = general();
+    let b = Matrix::new( vec![vec![c( 1., 0. )], vec![c( 0., 2. )], vec![c( -3., 1. )]] ).unwrap();
+
+    let x = a.solve( &b ).unwrap();
+    assert!( ( &a * &x ).approx_eq( &b, 1e-12 ) );
+}
+#[test]
+fn rank_deficient_matrices_are_singular () {
+    // The third row is the sum of the first two
+    let a = real( &[&[1., 2., 3.], &[4., 5., 6.], &[5., 7., 9.]] );
+
+    assert!( a.determinant().unwrap().modulus() < 1e-12 );
+    assert_eq!( a.clone().invert().err(), Some(QuantumError::SingularMatrix) );
+    assert_eq!( a.solve( &real( &[&[1.], &[0.], &[0.]] ) ).err(), Some(QuantumError::SingularMatrix) );
+}
+
+/* Eigendecomposition */
+fn diagonal ( values: &[ComplexNumber] ) -> Matrix {
+    let mut ret = Matrix::from_dimensions( values.len(), values.len() );
+    for (i, &value) in values.iter().enumerate() {
+        ret[i][i] = value;
+    }
+    ret
+}
+#[test]
+fn hermitian_eigenvectors_satisfy_av_equals_v_lambda () {
+    let a = general();
+    let hermitian = a.clone() + a.dagger();
+
+    let (eigenvalues, v) = hermitian.hermitian_eigen().unwrap();
+    assert!( eigenvalues.windows( 2 ).all(|pair| pair[0] <= pair[1]) );
+
+    let lambda = diagonal( &eigenvalues.iter().map(|&x| c( x, 0. )).collect::<Vec<_>>() );
+    assert!( ( &hermitian * &v ).approx_eq( &( &v * &lambda ), 1e-10 ) );
+    assert!( ( &v.dagger() * &v ).approx_eq( &Matrix::identity( 3 ), 1e-10 ) );
+}
+#[test]
+fn general_eigenvectors_satisfy_av_equals_v_lambda () {
+    let a = general();
+
+    let (eigenvalues, v) = a.eigen().unwrap();
+    assert!( ( &a * &v ).approx_eq( &( &v * &diagonal( &eigenvalues ) ), 1e-10 ) );
+
+    // A rotation has no real eigenvalues: e^(±iθ/2) for R_x(θ)
+    let rotation = qsharp_rs::Gate::R_x( 1.2 );
+    let (eigenvalues, v) = rotation.eigen().unwrap();
+    assert!( ( &rotation * &v ).approx_eq( &( &v * &diagonal( &eigenvalues ) ), 1e-10 ) );
+    assert!( eigenvalues.iter().all(|z| ( z.modulus() - 1. ).abs() < 1e-10 && z.b.abs() > 0.5) );
+}
+#[test]
+fn hermitian_eigen_rejects_non_hermitian_input () {
+    assert_eq!( general().hermitian_eigen().err(), Some(QuantumError::NonHermitianMatrix) );
+}
+
+/* Matrix functions */
+#[test]
+fn exponential_of_a_pauli_is_a_rotation () {
+    use std::f64::consts::FRAC_PI_2;
+
+    // e^{-iπ/2 X} = cos(π/2) I - i sin(π/2) X = -iX
+    let exponent = qsharp_rs::Gate::X() * c( 0., -FRAC_PI_2 );
+    let expected = qsharp_rs::Gate::X() * c( 0., -1. );
+    assert!( exponent.expm().unwrap().approx_eq( &expected, 1e-12 ) );
+
+    // logm undoes expm for a normal matrix away from the branch cut
+    let hermitian = ( general() + general().dagger() ) * c( 0.1, 0. );
+    assert!( hermitian.expm().unwrap().logm().unwrap().approx_eq( &hermitian, 1e-9 ) );
+}
+#[test]
+fn square_root_squares_back () {
+    let z = qsharp_rs::Gate::Z();
+    let root = z.sqrtm().unwrap();
+    assert!( ( &root * &root ).approx_eq( &z, 1e-12 ) );
+    // On the principal branch √Z is S, not S†
+    assert!( root.approx_eq( &qsharp_rs::Gate::S(), 1e-12 ) );
+
+    let x = qsharp_rs::Gate::X();
+    let root = x.sqrtm().unwrap();
+    assert!( ( &root * &root ).approx_eq( &x, 1e-12 ) );
+}
+#[test]
+fn fractional_powers_compose () {
+    let x: Matrix = qsharp_rs::Gate::X();
+    let half = x.powm( 0.5 ).unwrap();
+    assert!( ( &half * &half ).approx_eq( &x, 1e-12 ) );
+    assert!( half.approx_eq( &x.sqrtm().unwrap(), 1e-12 ) );
+
+    // X^¼ X^¾ = X, and X^0 = I
+    let product = &x.powm( 0.25 ).unwrap() * &x.powm( 0.75 ).unwrap();
+    assert!( product.approx_eq( &x, 1e-12 ) );
+    assert!( x.powm( 0. ).unwrap().approx_eq( &Matrix::identity( 2 ), 1e-12 ) );
+
+    // func applies f to each eigenvalue, so squaring matches the product
+    let hermitian = general() + general().dagger();
+    let squared = hermitian.func(|z| z * z).unwrap();
+    assert!( squared.approx_eq( &( &hermitian * &hermitian ), 1e-9 ) );
+}
+
+/* Singular value decomposition */
+fn check_svd ( a: &Matrix, rank: usize ) {
+    let (u, sigma, v_dagger) = a.svd().unwrap();
+    let k = a.rows.min( a.cols );
+    assert_eq!( (u.rows, u.cols), (a.rows, k) );
+    assert_eq!( (sigma.rows, sigma.cols), (k, k) );
+    assert_eq!( (v_dagger.rows, v_dagger.cols), (k, a.cols) );
+
+    assert!( ( &( &u * &sigma ) * &v_dagger ).approx_eq( a, 1e-10 ) );
+    for i in 0..k {
+        assert!( sigma[i][i].b == 0. && sigma[i][i].a >= 0. );
+        assert!( i == 0 || sigma[i][i].a <= sigma[i - 1][i - 1].a );
+    }
+    let nonzero = (0..k).filter(|&i| sigma[i][i].a > 1e-10).count();
+    assert_eq!( nonzero, rank );
+}
+#[test]
+fn svd_reconstructs_rectangular_matrices () {
+    let wide = Matrix::new(vec![
+        vec![c( 1., 2. ), c( 0., -1. ), c( 3., 0. )],
+        vec![c( -2., 0. ), c( 1., 1. ), c( 0.5, -0.5 )]
+    ]).unwrap();
+    check_svd( &wide, 2 );
+
+    let mut tall = wide.clone();
+    tall.adjunct();
+    check_svd( &tall, 2 );
+
+    check_svd( &general(), 3 );
+}
+#[test]
+fn svd_reconstructs_rank_deficient_matrices () {
+    // The third row is the sum of the first two
+    let deficient = real( &[&[1., 2., 3.], &[4., 5., 6.], &[5., 7., 9.]] );
+    check_svd( &deficient, 2 );
+
+    // An outer product has rank one
+    let outer = real( &[&[1.], &[2.], &[-1.], &[0.5]] ).outer_product( &real( &[&[3.], &[-1.], &[2.]] ) ).unwrap();
+    check_svd( &outer, 1 );
+
+    check_svd( &Matrix::from_dimensions( 3, 2 ), 0 );
+}
+#[test]
+fn polar_factors_are_unitary_and_positive () {
+    // Full rank, rank deficient, and a unitary that has drifted slightly
+    let drifted = qsharp_rs::Gate::H() + real( &[&[1e-3, 0.], &[0., -2e-3]] );
+    let deficient = real( &[&[1., 2., 3.], &[4., 5., 6.], &[5., 7., 9.]] );
+    for a in [general(), deficient, drifted] {
+        let (w, p) = a.polar().unwrap();
+        let n = a.rows;
+
+        assert!( ( &w.dagger() * &w ).approx_eq( &Matrix::identity( n ), 1e-10 ) );
+        assert!( p.approx_eq( &p.dagger(), 1e-10 ) );
+        let (eigenvalues, _) = p.hermitian_eigen().unwrap();
+        assert!( eigenvalues.iter().all(|&lambda| lambda > -1e-10) );
+        assert!( ( &w * &p ).approx_eq( &a, 1e-10 ) );
+    }
+
+    // A unitary is its own closest unitary, with P = I
+    let (w, p) = qsharp_rs::Gate::<f64>::H().polar().unwrap();
+    assert!( w.approx_eq( &qsharp_rs::Gate::H(), 1e-10 ) && p.approx_eq( &Matrix::identity( 2 ), 1e-10 ) );
+}
+
+/* Views */
+// Distinct, non-trivial entries, for checking the strides pick the right ones
+fn filled ( rows: usize, cols: usize ) -> Matrix {
+    Matrix::new(
+        (0..rows).map(|r| (0..cols).map(|col| c( ( r * cols + col ) as f64 * 0.25 - 3., r as f64 - col as f64 * 0.5 )).collect()).collect()
+    ).unwrap()
+}
+#[test]
+fn mul_into_transposed_views_matches_the_dense_product () {
+    // Larger than one 64 x 64 tile, so the blocked loops wrap around
+    let (a, b) = (filled( 70, 3 ), filled( 66, 70 ));
+    let (mut a_t, mut b_t) = (a.clone(), b.clone());
+    a_t.transpose();
+    b_t.transpose();
+
+    let mut out = Matrix::from_dimensions( 3, 66 );
+    a.view().transpose().mul_into( b.view().transpose(), &mut out ).unwrap();
+    assert!( out.approx_eq( &( &a_t * &b_t ), 1e-9 ) );
+
+    // out's old contents are overwritten, not added to
+    a.view().transpose().mul_into( b.view().transpose(), &mut out ).unwrap();
+    assert!( out.approx_eq( &( &a_t * &b_t ), 1e-9 ) );
+
+    let mut square = Matrix::from_dimensions( 70, 70 );
+    b.view().transpose().mul_into( b.view(), &mut square ).unwrap();
+    assert!( square.approx_eq( &( &b_t * &b ), 1e-9 ) );
+}
+#[test]
+fn mul_into_blocks_matches_the_dense_product () {
+    let (a, b) = (filled( 5, 6 ), filled( 4, 7 ));
+    let a_block = Matrix::new( (1..4).map(|r| a[r][2..6].to_vec()).collect() ).unwrap();
+    let b_block = Matrix::new( (0..4).map(|r| b[r][3..5].to_vec()).collect() ).unwrap();
+
+    let mut out = Matrix::from_dimensions( 3, 2 );
+    a.view().block( 1, 2, 3, 4 ).unwrap().mul_into( b.view().block( 0, 3, 4, 2 ).unwrap(), &mut out ).unwrap();
+    assert!( out.approx_eq( &( &a_block * &b_block ), 1e-12 ) );
+
+    // A block of a transpose, and a row times a column
+    let mut b_t = b.clone();
+    b_t.transpose();
+    let b_t_block = Matrix::new( (1..5).map(|r| b_t[r][1..4].to_vec()).collect() ).unwrap();
+    let mut out = Matrix::from_dimensions( 3, 3 );
+    a.view().block( 1, 2, 3, 4 ).unwrap().mul_into( b.view().transpose().block( 1, 1, 4, 3 ).unwrap(), &mut out ).unwrap();
+    assert!( out.approx_eq( &( &a_block * &b_t_block ), 1e-12 ) );
+
+    let mut dot = Matrix::from_dimensions( 1, 1 );
+    a.view().row( 4 ).unwrap().mul_into( a.view().transpose().column( 4 ).unwrap(), &mut dot ).unwrap();
+    let expected = a[4].iter().zip( a[4].iter() ).fold(c( 0., 0. ), |sum, (&x, &y)| sum + x * y);
+    assert!( dot[0][0].approx_eq( &expected, 1e-12 ) );
+}
+#[test]
+fn views_reject_bad_shapes () {
+    let (a, b) = (filled( 3, 4 ), filled( 4, 2 ));
+
+    let mut wrong = Matrix::from_dimensions( 2, 3 );
+    assert_eq!(
+        a.mul_into( &b, &mut wrong ),
+        Err(QuantumError::DimensionMismatch { expected: (3, 2), found: (2, 3) })
+    );
+    assert_eq!(
+        a.view().mul_into( b.view().transpose(), &mut wrong ).err(),
+        Some(QuantumError::DimensionMismatch { expected: (4, 4), found: (2, 4) })
+    );
+
+    assert_eq!(
+        a.view().block( 1, 2, 3, 1 ).err(),
+        Some(QuantumError::DimensionMismatch { expected: (3, 4), found: (4, 3) })
+    );
+    assert!( a.view().transpose().block( 0, 0, 4, 4 ).is_err() );
+    assert!( a.view().row( 3 ).is_err() && a.view().column( 4 ).is_err() );
+}
+
+/* Comparison */
+#[test]
+fn relative_eq_scales_with_the_entries () {
+    let large = real( &[&[1e12, -3e15]] );
+    let nudged = real( &[&[1e12 + 1., -3e15 + 100.]] );
+    // Off by far more than eps absolutely, but not relative to the size
+    assert!( !large.approx_eq( &nudged, 1e-10 ) );
+    assert!( large.relative_eq( &nudged, 1e-10 ) );
+    assert!( !large.relative_eq( &real( &[&[1e12 + 1e6, -3e15]] ), 1e-10 ) );
+
+    // Near zero the tolerance is absolute, so rounding noise still equals 0
+    let small = real( &[&[1e-17, 0.]] );
+    assert!( small.relative_eq( &real( &[&[0., -1e-17]] ), 1e-10 ) );
+    assert!( !small.relative_eq( &real( &[&[1e-3, 0.]] ), 1e-10 ) );
+
+    assert!( !large.relative_eq( &real( &[&[1e12], &[-3e15]] ), 1e-10 ) );
+}
+#[test]
+fn inverse_tensor_product_ignores_rounding_in_the_base () {
+    let b = real( &[&[1., 2.], &[3., 4.]] );
+    let product = real( &[&[1., 0.], &[0., 0.]] ).tensor_product( &b );
+    // A base entry of 1e-17 is a zero that picked up rounding noise, and
+    // its block mustn't overwrite b with 0 / 1e-17
+    let base = real( &[&[1., 0.], &[0., 1e-17]] );
+    assert!( product.inverse_tensor_product( base ).unwrap().approx_eq( &b, 1e-12 ) );
+
+    let noise = real( &[&[1e-17, 0.], &[0., -1e-17]] );
+    assert_eq!( product.inverse_tensor_product( noise ).err(), Some(QuantumError::DivisionByZero) );
+}
+
+/* Products */
+#[test]
+fn products_propagate_non_finite_entries () {
+    let a = real( &[&[0., 1.], &[1., 0.]] );
+    let b = real( &[&[f64::NAN, 0.], &[0., f64::INFINITY]] );
+
+    // 0 * NaN and 0 * ∞ are NaN under IEEE 754, not 0
+    let product = &a * &b;
+    assert!( product[0][0].a.is_nan() && product[1][1].a.is_nan() );
+    assert!( product[0][1].a.is_infinite() && product[1][0].a.is_nan() );
+}
+#[test]
+fn unconverged_decompositions_are_errors () {
+    // NaN never passes a convergence test, so every iteration runs out
+    let mut poisoned = general();
+    poisoned[1][2] = c( f64::NAN, 0. );
+
+    assert_eq!( poisoned.eigen().err(), Some(QuantumError::NoConvergence) );
+    assert_eq!( poisoned.svd().err(), Some(QuantumError::NoConvergence) );
+    assert_eq!( poisoned.sqrtm().err(), Some(QuantumError::NoConvergence) );
+}
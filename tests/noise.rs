@@ -0,0 +1,48 @@
+use qsharp_rs::{
+    ComplexNumber,
+    Matrix,
+    System,
+    Backend,
+    Channel,
+    QuantumError
+};
+
+fn density_after ( prepare: impl Fn(&mut System), channel: &Channel ) -> Matrix {
+    let mut system = System::with_backend( Backend::DensityMatrix );
+    system.allocate();
+    prepare( &mut system );
+    system.apply_channel( channel, &[0] ).unwrap();
+    system.density_matrix()
+}
+
+#[test]
+fn amplitude_damping_decays_one_towards_zero () {
+    for gamma in [0.0, 0.3, 1.0] {
+        let channel = Channel::amplitude_damping( gamma ).unwrap();
+        let rho = density_after(|system| { system.qubit( 0 ).unwrap().X(); }, &channel );
+
+        assert!( ( rho[1][1].a - ( 1. - gamma ) ).abs() < 1e-12 );
+        assert!( ( rho[0][0].a - gamma ).abs() < 1e-12 );
+        assert!( rho[0][1].modulus() < 1e-12 );
+    }
+}
+#[test]
+fn phase_damping_shrinks_coherence_but_not_populations () {
+    let lambda = 0.36;
+    let channel = Channel::phase_damping( lambda ).unwrap();
+    let rho = density_after(|system| { system.qubit( 0 ).unwrap().H(); }, &channel );
+
+    assert!( ( rho[0][0].a - 0.5 ).abs() < 1e-12 && ( rho[1][1].a - 0.5 ).abs() < 1e-12 );
+    // <0|ρ|1> goes from 1/2 to √(1 - λ)/2
+    let coherence = 0.5 * ( 1. - lambda ).sqrt();
+    assert!( ( rho[0][1].a - coherence ).abs() < 1e-12 && ( rho[1][0].a - coherence ).abs() < 1e-12 );
+}
+#[test]
+fn kraus_sets_must_be_trace_preserving () {
+    let half = Matrix::identity( 2 ) * ComplexNumber::new( 0.5, 0. );
+    assert_eq!( Channel::new( vec![half.clone()] ).err(), Some(QuantumError::IncompleteKraus) );
+    assert_eq!( Channel::new( vec![half.clone(), half] ).err(), Some(QuantumError::IncompleteKraus) );
+
+    let scaled = Matrix::identity( 2 ) * ComplexNumber::new( 0.5f64.sqrt(), 0. );
+    assert!( Channel::new( vec![scaled.clone(), scaled] ).is_ok() );
+}
@@ -0,0 +1,131 @@
+use qsharp_rs::{
+    ComplexNumber,
+    Matrix,
+    Gate,
+    SparseMatrix,
+    System,
+    QuantumError
+};
+
+fn c ( a: f64, b: f64 ) -> ComplexNumber {
+    ComplexNumber::new( a, b )
+}
+// A mostly-zero complex matrix, with a full row and an empty one
+fn sparse_dense () -> Matrix {
+    Matrix::new(vec![
+        vec![c( 0., 0. ), c( 2., -1. ), c( 0., 0. )],
+        vec![c( 0., 0. ), c( 0., 0. ), c( 0., 0. )],
+        vec![c( 1., 1. ), c( 0., 3. ), c( -4., 0. )]
+    ]).unwrap()
+}
+
+#[test]
+fn dense_round_trip () {
+    let dense = sparse_dense();
+    let sparse = SparseMatrix::from( &dense );
+
+    assert_eq!( sparse.nnz(), 4 );
+    assert_eq!( sparse.to_dense(), dense );
+    assert_eq!( Matrix::from( &sparse ), dense );
+    assert_eq!( sparse.get( 2, 1 ), c( 0., 3. ) );
+    assert_eq!( sparse.get( 1, 1 ), c( 0., 0. ) );
+}
+#[test]
+fn triplets_sum_duplicates_and_drop_zeros () {
+    let sparse = SparseMatrix::from_triplets( 2, 2, vec![
+        (1, 0, c( 1., 0. )),
+        (0, 1, c( 2., 0. )),
+        (1, 0, c( 0., 2. )),
+        // Cancels out entirely
+        (0, 0, c( 3., 0. )),
+        (0, 0, c( -3., 0. )),
+        (1, 1, c( 0., 0. ))
+    ]).unwrap();
+
+    assert_eq!( sparse.nnz(), 2 );
+    assert_eq!( sparse.triplets(), vec![(0, 1, c( 2., 0. )), (1, 0, c( 1., 2. ))] );
+
+    assert_eq!(
+        SparseMatrix::<f64>::from_triplets( 2, 2, vec![(2, 0, c( 1., 0. ))] ).err(),
+        Some(QuantumError::DimensionMismatch { expected: (2, 2), found: (3, 1) })
+    );
+}
+#[test]
+fn products_match_the_dense_ones () {
+    let a = sparse_dense();
+    let b = Gate::H().tensor_product( &Gate::I() ).tensor_product( &Gate::I() );
+    let b = Matrix::new( (0..3).map(|r| b[r][..3].to_vec()).collect() ).unwrap();
+    let (sparse_a, sparse_b) = (SparseMatrix::from( &a ), SparseMatrix::from( &b ));
+
+    assert!( ( &sparse_a * &sparse_b ).to_dense().approx_eq( &( &a * &b ), 1e-12 ) );
+    assert!( sparse_a.checked_mul( &sparse_b ).unwrap().to_dense().approx_eq( &( &a * &b ), 1e-12 ) );
+    assert!( ( &sparse_a * &b ).approx_eq( &( &a * &b ), 1e-12 ) );
+
+    // Entries that cancel aren't stored
+    let row = SparseMatrix::from( &Matrix::new( vec![vec![c( 1., 0. ), c( 1., 0. )]] ).unwrap() );
+    let column = SparseMatrix::from( &Matrix::new( vec![vec![c( 1., 0. )], vec![c( -1., 0. )]] ).unwrap() );
+    assert_eq!( ( &row * &column ).nnz(), 0 );
+
+    let x = SparseMatrix::from( &Gate::X() );
+    assert!( matches!( sparse_a.checked_mul( &x ), Err(QuantumError::DimensionMismatch { .. }) ) );
+}
+#[test]
+fn vector_products_check_the_length () {
+    let a = sparse_dense();
+    let sparse = SparseMatrix::from( &a );
+    let amplitudes = vec![c( 1., 0. ), c( 0., 1. ), c( 2., -1. )];
+
+    let product = sparse.mul_vector( &amplitudes ).unwrap();
+    let column = Matrix::new( amplitudes.iter().map(|&x| vec![x]).collect() ).unwrap();
+    let expected = &a * &column;
+    assert!( (0..3).all(|r| product[r] == expected[r][0]) );
+
+    assert_eq!(
+        sparse.mul_vector( &amplitudes[..2] ).err(),
+        Some(QuantumError::DimensionMismatch { expected: (3, 1), found: (2, 1) })
+    );
+}
+#[test]
+fn tensor_product_and_adjunct_match_the_dense_ones () {
+    let a = sparse_dense();
+    let b = Gate::Y();
+    let sparse = SparseMatrix::from( &a ).tensor_product( &SparseMatrix::from( &b ) );
+    assert_eq!( sparse.to_dense(), a.tensor_product( &b ) );
+    assert_eq!( sparse.nnz(), 4 * 2 );
+
+    assert_eq!( SparseMatrix::from( &a ).adjunct().to_dense(), a.dagger() );
+    assert_eq!( SparseMatrix::<f64>::identity( 4 ).to_dense(), Matrix::identity( 4 ) );
+}
+
+/* Full-system operators */
+#[test]
+fn sparse_gates_match_dense_gates () {
+    let mut system = System::new();
+    for _ in 0..4 {
+        system.allocate();
+    }
+    let mut cnot = Matrix::identity( 4 );
+    cnot[2][2] = c( 0., 0. );
+    cnot[3][3] = c( 0., 0. );
+    cnot[2][3] = c( 1., 0. );
+    cnot[3][2] = c( 1., 0. );
+
+    let layouts: Vec<Vec<(usize, Matrix)>> = vec![
+        vec![(1, Gate::H())],
+        // A controlled gate on registers 2 and 3
+        vec![(2, cnot.clone())],
+        // Non-adjacent gates, given out of order
+        vec![(3, Gate::T()), (0, Gate::X())],
+        vec![(0, cnot), (3, Gate::R_y( 0.4 ))]
+    ];
+    for inputs in layouts {
+        let dense = system.build_gate( inputs.clone() ).unwrap();
+        let sparse = system.build_sparse_gate( inputs ).unwrap();
+        assert!( sparse.to_dense().approx_eq( &dense, 1e-12 ) );
+    }
+
+    assert_eq!(
+        system.build_sparse_gate( vec![(0, Gate::X()), (0, Gate::Z())] ).err(),
+        Some(QuantumError::DuplicateQubit( 0 ))
+    );
+}
@@ -0,0 +1,298 @@
+use qsharp_rs::{
+    System,
+    Backend,
+    Pauli,
+    NoiseModel,
+    Channel,
+    Gate,
+    Matrix,
+    ComplexNumber,
+    SeededRng,
+    QuantumError
+};
+
+#[test]
+fn sample_of_no_registers_is_keyed_by_the_empty_bitstring () {
+    let mut system = System::seeded( 1 );
+    system.allocate().H();
+
+    let histogram = system.sample( 100, &[] ).unwrap();
+    assert_eq!( histogram.len(), 1 );
+    assert_eq!( histogram.get( "" ), Some(&100) );
+
+    assert!( system.sample( 0, &[] ).unwrap().is_empty() );
+}
+#[test]
+fn sample_keys_have_one_digit_per_register () {
+    let mut system = System::seeded( 1 );
+    system.allocate().X();
+    system.allocate();
+
+    let histogram = system.sample( 10, &[0, 1] ).unwrap();
+    assert_eq!( histogram.get( "10" ), Some(&10) );
+}
+
+#[test]
+fn backend_constructors_default_to_f64 () {
+    let system = System::with_backend( Backend::DensityMatrix );
+    assert_eq!( system.backend(), Backend::DensityMatrix );
+
+    let single: System<f32> = System::from_backend( Backend::SplitComplex, SeededRng::new(1) );
+    assert_eq!( single.backend(), Backend::SplitComplex );
+}
+// The same seeded circuit on both pure-state layouts, compared exactly:
+// SplitComplex does the same arithmetic in the same order as StateVector
+#[test]
+fn split_complex_matches_state_vector_bit_for_bit () {
+    let run = |backend: Backend| {
+        let mut system = System::with_backend_and_rng( backend, SeededRng::new(11) );
+        for _ in 0..5 {
+            system.allocate();
+        }
+        let mut outcomes = Vec::new();
+
+        for target in 0..5 {
+            system.qubit( target ).unwrap().H().T().R_y( 0.3 + target as f64 ).S_adj();
+        }
+        system.CNOT( 0, 3 ).unwrap();
+        system.CCNOT( 4, 1, 2 ).unwrap();
+        system.controlled_on( &[(3, false)], 0, &Gate::R_x( 1.1 ) ).unwrap();
+        system.CR_z( &[2], 4, -0.7 ).unwrap();
+        system.SWAP( 1, 4 ).unwrap();
+        system.CSWAP( 0, 2, 3 ).unwrap();
+
+        // A two-register unitary on reversed, non-adjacent targets
+        let pair = Gate::R_x( 0.4 ).tensor_product( &Gate::H() );
+        system.apply( &pair, &[3, 1] ).unwrap();
+        system.apply_channel( &Channel::depolarizing( 0.3 ).unwrap(), &[2] ).unwrap();
+        system.apply_channel( &Channel::amplitude_damping( 0.4 ).unwrap(), &[0] ).unwrap();
+
+        outcomes.push( system.measure( 4 ).unwrap() );
+        system.qubit( 0 ).unwrap().R_z( 0.9 );
+        outcomes.push( system.measure_in_basis( Pauli::X, 1 ).unwrap() );
+        outcomes.push( system.measure_pauli_product( &[(Pauli::Y, 0), (Pauli::Z, 2)] ).unwrap() );
+
+        (system.state_vector().unwrap(), outcomes)
+    };
+
+    let (split, split_outcomes) = run( Backend::SplitComplex );
+    let (interleaved, interleaved_outcomes) = run( Backend::StateVector );
+    assert_eq!( split_outcomes, interleaved_outcomes );
+    assert!( split.data == interleaved.data, "SplitComplex diverged from StateVector" );
+}
+#[test]
+fn seeded_density_matrix_runs_are_reproducible () {
+    let run = || {
+        let mut system = System::with_backend_and_rng( Backend::DensityMatrix, SeededRng::new(7) );
+        system.allocate().H();
+        system.allocate();
+        system.CNOT( 0, 1 ).unwrap();
+        system.sample( 200, &[0, 1] ).unwrap()
+    };
+
+    assert_eq!( run(), run() );
+}
+
+#[test]
+fn basis_measurements_go_through_readout_errors () {
+    let mut system = System::seeded( 1 );
+    system.set_noise_model( NoiseModel::new().readout_flip( 0, 1.0 ).unwrap() );
+    system.allocate();
+
+    // |0> always misread as 1, whichever way it is measured
+    assert!( system.measure_in_basis( Pauli::Z, 0 ).unwrap() );
+    assert!( system.measure( 0 ).unwrap() );
+
+    // |+> is the +1 eigenstate of X, so only the misread makes it true
+    system.qubit( 0 ).unwrap().H();
+    assert!( system.measure_in_basis( Pauli::X, 0 ).unwrap() );
+}
+#[test]
+fn pauli_products_flip_with_each_misread_register () {
+    let model = || NoiseModel::new().readout_flip( 0, 1.0 ).unwrap();
+
+    // |00> has even parity; one register always misread makes it odd
+    let mut system = System::seeded( 1 );
+    system.set_noise_model( model() );
+    system.allocate();
+    system.allocate();
+    assert!( system.measure_pauli_product( &[(Pauli::Z, 0), (Pauli::Z, 1)] ).unwrap() );
+
+    // Two registers always misread flip it back
+    let mut system = System::seeded( 1 );
+    system.set_noise_model( model().readout_flip( 1, 1.0 ).unwrap() );
+    system.allocate();
+    system.allocate();
+    assert!( !system.measure_pauli_product( &[(Pauli::Z, 0), (Pauli::Z, 1)] ).unwrap() );
+
+    // An identity factor isn't read, so its readout error doesn't apply
+    let mut system = System::seeded( 1 );
+    system.set_noise_model( model() );
+    system.allocate();
+    system.allocate();
+    assert!( !system.measure_pauli_product( &[(Pauli::I, 0), (Pauli::Z, 1)] ).unwrap() );
+}
+
+// Probabilities of every basis state, which all three backends can report
+fn assert_basis_state ( system: &System, index: usize ) {
+    let all: Vec<usize> = (0..system.qubits()).collect();
+    let probabilities = system.probabilities( &all ).unwrap();
+    for (i, p) in probabilities.into_iter().enumerate() {
+        let expected = if i == index { 1. } else { 0. };
+        assert!( ( p - expected ).abs() < 1e-12, "P(|{:0width$b}>) = {}", i, p, width = system.qubits() );
+    }
+}
+fn cnot () -> Matrix {
+    let mut ret = Matrix::identity( 4 );
+    ret[2][2] = ComplexNumber::new( 0., 0. );
+    ret[3][3] = ComplexNumber::new( 0., 0. );
+    ret[2][3] = ComplexNumber::new( 1., 0. );
+    ret[3][2] = ComplexNumber::new( 1., 0. );
+    ret
+}
+
+/* Multi-register gates */
+#[test]
+fn apply_takes_targets_in_gate_order () {
+    for backend in [Backend::StateVector, Backend::SplitComplex, Backend::DensityMatrix] {
+        // |001>, with targets[0] = 2 as the control and register 0 the target
+        let mut system = System::with_backend( backend );
+        system.allocate();
+        system.allocate();
+        system.allocate().X();
+        system.apply( &cnot(), &[2, 0] ).unwrap();
+        assert_basis_state( &system, 0b101 );
+
+        // In ascending order register 0 is the control, which is |1> now
+        system.apply( &cnot(), &[0, 2] ).unwrap();
+        assert_basis_state( &system, 0b100 );
+
+        // A control of |0> leaves the target alone
+        system.apply( &cnot(), &[1, 2] ).unwrap();
+        assert_basis_state( &system, 0b100 );
+    }
+}
+#[test]
+fn apply_rejects_bad_targets_and_gates () {
+    let mut system = System::new();
+    system.allocate();
+    system.allocate();
+
+    assert_eq!( system.apply( &cnot(), &[1, 1] ).err(), Some(QuantumError::DuplicateQubit( 1 )) );
+    assert_eq!( system.apply( &cnot(), &[0, 2] ).err(), Some(QuantumError::QubitOutOfRange { index: 2, qubits: 2 }) );
+    assert_eq!(
+        system.apply( &cnot(), &[0] ).err(),
+        Some(QuantumError::DimensionMismatch { expected: (2, 2), found: (4, 4) })
+    );
+
+    let mut not_unitary = Matrix::identity( 4 );
+    not_unitary[0][1] = ComplexNumber::new( 1., 0. );
+    assert_eq!( system.apply( &not_unitary, &[0, 1] ).err(), Some(QuantumError::NonUnitaryGate) );
+
+    // Nothing ran
+    assert_basis_state( &system, 0b00 );
+}
+
+/* Negative controls */
+#[test]
+fn negative_controls_fire_on_zero () {
+    let mut system = System::new();
+    system.allocate();
+    system.allocate();
+
+    system.controlled_on( &[(0, false)], 1, &Gate::X() ).unwrap();
+    assert_basis_state( &system, 0b01 );
+
+    // Register 0 is |0>, so a positive control on it doesn't fire
+    system.controlled_on( &[(0, true)], 1, &Gate::X() ).unwrap();
+    assert_basis_state( &system, 0b01 );
+}
+#[test]
+fn mixed_controls_need_every_condition () {
+    for backend in [Backend::StateVector, Backend::SplitComplex, Backend::DensityMatrix] {
+        let mut system = System::with_backend( backend );
+        system.allocate().X();
+        system.allocate();
+        system.allocate();
+
+        // Fires: register 0 is |1> and register 1 is |0>
+        system.controlled_on( &[(0, true), (1, false)], 2, &Gate::X() ).unwrap();
+        assert_basis_state( &system, 0b101 );
+
+        // Doesn't fire: register 2 is |1>, not |0>
+        system.controlled_on( &[(0, true), (2, false)], 1, &Gate::X() ).unwrap();
+        assert_basis_state( &system, 0b101 );
+
+        // Fires: both are |1>, given in either order
+        system.controlled_on( &[(2, true), (0, true)], 1, &Gate::X() ).unwrap();
+        assert_basis_state( &system, 0b111 );
+
+        // Fires again, now that register 1 is |1>
+        system.controlled_on( &[(1, true), (0, true)], 2, &Gate::X() ).unwrap();
+        assert_basis_state( &system, 0b110 );
+    }
+}
+#[test]
+fn controls_must_not_include_the_target () {
+    let mut system = System::new();
+    system.allocate();
+    system.allocate();
+
+    assert_eq!( system.controlled_on( &[(1, false)], 1, &Gate::X() ).err(), Some(QuantumError::DuplicateQubit( 1 )) );
+    assert_eq!( system.controlled_on( &[(0, true), (0, false)], 1, &Gate::X() ).err(), Some(QuantumError::DuplicateQubit( 0 )) );
+    assert_basis_state( &system, 0b00 );
+}
+
+/* Reduced states */
+fn projector ( bits: &[u8] ) -> Matrix {
+    let mut ret = Matrix::identity( 1 );
+    for &bit in bits {
+        let mut factor = Matrix::from_dimensions( 2, 2 );
+        factor[bit as usize][bit as usize] = ComplexNumber::new( 1., 0. );
+        ret = ret.tensor_product( &factor );
+    }
+    ret
+}
+#[test]
+fn reduced_states_follow_the_order_of_the_kept_registers () {
+    // |100>
+    let mut system = System::new();
+    system.allocate().X();
+    system.allocate();
+    system.allocate();
+
+    assert!( system.reduced_state( &[2, 0] ).unwrap().approx_eq( &projector( &[0, 1] ), 1e-12 ) );
+    assert!( system.reduced_state( &[0, 2] ).unwrap().approx_eq( &projector( &[1, 0] ), 1e-12 ) );
+    assert!( system.reduced_state( &[1, 0, 2] ).unwrap().approx_eq( &projector( &[0, 1, 0] ), 1e-12 ) );
+    assert_eq!( system.reduced_state( &[0, 0] ).err(), Some(QuantumError::DuplicateQubit( 0 )) );
+}
+#[test]
+fn half_of_a_bell_pair_is_maximally_mixed () {
+    for backend in [Backend::StateVector, Backend::SplitComplex, Backend::DensityMatrix] {
+        let mut system = System::with_backend( backend );
+        system.allocate().H();
+        system.allocate();
+        system.CNOT( 0, 1 ).unwrap();
+
+        let half = Matrix::identity( 2 ) * ComplexNumber::new( 0.5, 0. );
+        assert!( system.reduced_state( &[0] ).unwrap().approx_eq( &half, 1e-12 ) );
+        assert!( system.reduced_state( &[1] ).unwrap().approx_eq( &half, 1e-12 ) );
+    }
+}
+#[test]
+fn reduced_state_matches_partial_trace_of_the_density_matrix () {
+    let mut system = System::with_backend_and_rng( Backend::DensityMatrix, SeededRng::new(3) );
+    system.allocate().H();
+    system.allocate().R_y( 0.8 );
+    system.allocate().T();
+    system.CNOT( 0, 2 ).unwrap();
+    system.CR_x( &[1], 0, 0.6 ).unwrap();
+    system.apply_channel( &Channel::amplitude_damping( 0.3 ).unwrap(), &[1] ).unwrap();
+
+    let rho = system.density_matrix();
+    for keep in [vec![0], vec![2], vec![2, 0], vec![0, 2], vec![1, 2, 0]] {
+        let reduced = system.reduced_state( &keep ).unwrap();
+        assert!( reduced.approx_eq( &rho.partial_trace( &keep ).unwrap(), 1e-12 ), "{:?}", keep );
+    }
+    assert!( rho.partial_trace( &[0, 1, 2] ).unwrap().approx_eq( &rho, 1e-12 ) );
+}